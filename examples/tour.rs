@@ -60,25 +60,12 @@ fn main() -> GmshResult<()> {
     // won't compile
     //geom.remove_point(line);
 
-    // most gmsh operations are limited to a group of specific geometry types
-
-    // this next operation only works on curves and lines, so calling it with a
-    // point tag won't compile.
-    // curve_or_surface_op(p1);
-
-    // either curves or surfaces are both fine though, without any obligation on
-    // the caller for an explicit conversion into the right type that we
-    // use behind the scenes
-
-    geom.curve_or_surface_op(line);
-
     let l1 = geom.add_line(p1, p2)?;
     let l2 = geom.add_line(p1, p2)?;
     let l3 = geom.add_line(p1, p2)?;
     let l4 = geom.add_line(p1, p2)?;
 
     // let s = geom.add_surface(&[l1, -l2, l3, l4])?;
-    // geom.curve_or_surface_op(s);
 
     // lines (curves) have a direction, from start to end.
     // you can reverse that direction of a given CurveTag using a negative sign.
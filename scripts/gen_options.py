@@ -0,0 +1,175 @@
+#!/usr/bin/env python3
+"""Generate the typed option catalogue from Gmsh's option table.
+
+Gmsh declares every option, with its default and help text, in
+`Common/DefaultOptions.h` of its source tree. Run this script on the file from
+the Gmsh version gmsh-sys binds to:
+
+    python3 scripts/gen_options.py gmsh-4.4.1-source/Common/DefaultOptions.h > src/option_table.rs
+
+String options with build-dependent defaults (e.g. `General.Version`) get an
+empty default. Other options whose defaults aren't plain literals are skipped
+with a warning on stderr.
+"""
+
+import re
+import sys
+
+# View options are set per view, as `View[n].Name`, so they aren't catalogued
+CATEGORIES = ["General", "Geometry", "Mesh", "Solver", "PostProcessing", "Print"]
+TABLE = re.compile(r"StringX(String|Number|Color)\s+(\w+)Options_\w+\[\]\s*=\s*\{")
+IDENT = re.compile(r"^[A-Za-z_]\w*$")
+NUMBER = re.compile(r"^[-+]?(\d+\.?\d*|\.\d+)([eE][-+]?\d+)?$")
+CONSTANTS = {"M_PI": 3.141592653589793}
+
+
+def warn(message):
+    print("gen_options: " + message, file=sys.stderr)
+
+
+def strip_preprocessor(source):
+    # keep the first branch of conditional defaults
+    lines, skipping, depth = [], False, 0
+    for line in source.splitlines():
+        stripped = line.strip()
+        if stripped.startswith("#if"):
+            depth += 1
+        elif stripped.startswith(("#else", "#elif")) and depth == 1:
+            skipping = True
+        elif stripped.startswith("#endif"):
+            depth -= 1
+            if depth == 0:
+                skipping = False
+        elif not skipping and not stripped.startswith(("#", "//")):
+            lines.append(line)
+    return "\n".join(lines)
+
+
+def entries(source, start):
+    """Split the table starting at `start` into entries of top-level fields."""
+    i, depth, field, fields, in_string = start, 1, "", [], False
+    while i < len(source):
+        c = source[i]
+        if in_string:
+            field += c
+            if c == "\\":
+                field += source[i + 1]
+                i += 1
+            elif c == '"':
+                in_string = False
+        elif c == '"':
+            in_string = True
+            field += c
+        elif c in "{(":
+            depth += 1
+            if not (c == "{" and depth == 2):
+                field += c
+        elif c in "})":
+            depth -= 1
+            if depth == 0:
+                return
+            if c == "}" and depth == 1:
+                fields.append(field.strip())
+                yield fields
+                field, fields = "", []
+            else:
+                field += c
+        elif c == "," and depth == 2:
+            fields.append(field.strip())
+            field = ""
+        elif depth >= 2:
+            field += c
+        i += 1
+
+
+def c_string(field):
+    """Join adjacent C string literals, or None if it isn't one."""
+    parts = re.findall(r'"((?:[^"\\]|\\.)*)"', field)
+    rest = re.sub(r'"((?:[^"\\]|\\.)*)"', "", field).strip()
+    if not parts or rest:
+        return None
+    return "".join(parts)
+
+
+def number(field):
+    field = field.strip().rstrip("f")
+    sign = -1. if field.startswith("-") else 1.
+    if field.lstrip("-+") in CONSTANTS:
+        return sign * CONSTANTS[field.lstrip("-+")]
+    if NUMBER.match(field):
+        return float(field)
+    return None
+
+
+def color(field):
+    match = re.match(r"PACK_COLOR\(\s*(\d+)\s*,\s*(\d+)\s*,\s*(\d+)\s*,\s*\d+\s*\)", field)
+    return match and "({}, {}, {})".format(*match.groups())
+
+
+def parse(source):
+    options = {category: [] for category in CATEGORIES}
+    for table in TABLE.finditer(source):
+        kind, category = table.group(1), table.group(2)
+        if category not in options:
+            continue
+        for fields in entries(source, table.end()):
+            name = c_string(fields[1]) if len(fields) > 1 else None
+            if not name:
+                continue
+            full_name = "{}.{}".format(category, name)
+            help_text = c_string(fields[-1]) or ""
+            if kind == "Number":
+                value = number(fields[3])
+                default = value is not None and repr(value)
+                entry = (name, None, "number", default)
+            elif kind == "String":
+                value = c_string(fields[3])
+                if value is None:
+                    help_text += " (the default depends on the build)"
+                default = '"{}"'.format(value or "")
+                entry = (name, None, "string", default)
+            else:
+                default = color(fields[3])
+                entry = (name + "Color", "Color." + name, "color", default)
+            if not default:
+                warn("skipping {}, its default isn't a literal".format(full_name))
+            elif not IDENT.match(entry[0]):
+                warn("skipping {}, it isn't a Rust name".format(full_name))
+            elif any(entry[0] == other[0] for other, _ in options[category]):
+                warn("skipping {}, its name is taken".format(full_name))
+            else:
+                options[category].append((entry, help_text))
+    return options
+
+
+def render(options):
+    out = [
+        "// Generated by scripts/gen_options.py from Gmsh's option table. Don't edit",
+        "// it by hand, regenerate it when moving to a new Gmsh version.",
+        "options! {",
+    ]
+    for i, category in enumerate(CATEGORIES):
+        if i > 0:
+            out.append("")
+        out.append("    /// {} options.".format(category))
+        out.append("    {} {{".format(category))
+        for (name, suffix, kind, default), help_text in options[category]:
+            if help_text:
+                out.append("        /// {}".format(help_text.replace("\\n", " ")))
+            suffix = '["{}"]'.format(suffix) if suffix else ""
+            out.append("        {}{}: {} = {};".format(name, suffix, kind, default))
+        out.append("    }")
+    out.append("}")
+    return "\n".join(out) + "\n"
+
+
+def main():
+    if len(sys.argv) != 2:
+        sys.exit(__doc__)
+    with open(sys.argv[1]) as f:
+        source = strip_preprocessor(f.read())
+    sys.stdout.write(render(parse(source)))
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,12 @@
+//! Serve Gmsh requests from `RemoteGmsh` on standard input and output.
+
+use std::io;
+
+fn main() {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    if let Err(e) = rgmsh::remote::server::serve(stdin.lock(), stdout.lock()) {
+        eprintln!("rgmsh-server: {}", e);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,159 @@
+//! Configure Gmsh before initializing it.
+//!
+//! ```no_run
+//! # use rgmsh::{Gmsh, GmshResult};
+//! # use rgmsh::logger::Verbosity;
+//! # fn main() -> GmshResult<()> {
+//! // behave like `gmsh` run with this program's command-line arguments
+//! let gmsh = Gmsh::builder()
+//!     .args(std::env::args())
+//!     .read_config_files(true)
+//!     .num_threads(4)
+//!     .verbosity(Verbosity::Warnings)
+//!     .initialize()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::logger::{self, Verbosity};
+use crate::{options, Gmsh, GmshError, GmshResult, INITIALIZED};
+use std::cell::RefCell;
+use std::ffi::CString;
+use std::marker::PhantomData;
+use std::os::raw::{c_char, c_int};
+use std::sync::atomic::Ordering;
+
+/// Settings for initializing Gmsh.
+///
+/// The defaults match [`Gmsh::initialize`]: no command-line arguments, no
+/// configuration files and messages sent to the `log` crate.
+#[derive(Debug, Clone)]
+pub struct GmshBuilder {
+    args: Vec<String>,
+    read_config_files: bool,
+    num_threads: Option<usize>,
+    verbosity: Option<Verbosity>,
+    terminal: bool,
+}
+
+impl Default for GmshBuilder {
+    fn default() -> GmshBuilder {
+        GmshBuilder {
+            args: vec!["gmsh".to_string()],
+            read_config_files: false,
+            num_threads: None,
+            verbosity: None,
+            terminal: false,
+        }
+    }
+}
+
+impl GmshBuilder {
+    /// The default settings.
+    pub fn new() -> GmshBuilder {
+        GmshBuilder::default()
+    }
+
+    /// Command-line arguments for Gmsh to parse, e.g. `["gmsh", "-nt", "4"]`.
+    ///
+    /// The first argument is the program name, like in `std::env::args()`.
+    pub fn args<I, S>(mut self, args: I) -> GmshBuilder
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.args = args.into_iter().map(Into::into).collect();
+        if self.args.is_empty() {
+            self.args.push("gmsh".to_string());
+        }
+        self
+    }
+
+    /// Read the user's Gmsh configuration files, e.g. `~/.gmshrc`.
+    pub fn read_config_files(mut self, read: bool) -> GmshBuilder {
+        self.read_config_files = read;
+        self
+    }
+
+    /// Use up to this many threads for meshing, or 0 for the system default.
+    pub fn num_threads(mut self, num_threads: usize) -> GmshBuilder {
+        self.num_threads = Some(num_threads);
+        self
+    }
+
+    /// Set how much Gmsh and rgmsh log.
+    pub fn verbosity(mut self, verbosity: Verbosity) -> GmshBuilder {
+        self.verbosity = Some(verbosity);
+        self
+    }
+
+    /// Also print Gmsh messages on the terminal.
+    pub fn terminal(mut self, terminal: bool) -> GmshBuilder {
+        self.terminal = terminal;
+        self
+    }
+
+    /// Initialize Gmsh with these settings.
+    ///
+    /// Fails with `AlreadyInitialized` if another context is still alive.
+    pub fn initialize(self) -> GmshResult<Gmsh> {
+        if INITIALIZED
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .is_err()
+        {
+            return Err(GmshError::AlreadyInitialized);
+        }
+
+        let c_args = match self
+            .args
+            .iter()
+            .map(|arg| CString::new(arg.as_str()))
+            .collect::<Result<Vec<_>, _>>()
+        {
+            Ok(c_args) => c_args,
+            Err(_) => {
+                INITIALIZED.store(false, Ordering::SeqCst);
+                return Err(GmshError::CInterface);
+            }
+        };
+        // Gmsh only reads the arguments
+        let mut argv: Vec<*mut c_char> = c_args.iter().map(|arg| arg.as_ptr() as *mut _).collect();
+
+        let mut ierr: c_int = 0;
+        unsafe {
+            gmsh_sys::gmshInitialize(
+                argv.len() as c_int,
+                argv.as_mut_ptr(),
+                self.read_config_files as c_int,
+                &mut ierr,
+            );
+        }
+        if ierr != 0 {
+            INITIALIZED.store(false, Ordering::SeqCst);
+            return Err(GmshError::Initialization);
+        }
+
+        // finalizes Gmsh if anything below fails
+        let mut gmsh = Gmsh {
+            unsynchronized: RefCell::new(Vec::new()),
+            current: RefCell::new(None),
+            _not_send: PhantomData,
+        };
+        // send logs to the log crate
+        logger::start()?;
+        if self.terminal {
+            gmsh.set_option(options::General::Terminal, 1.)?;
+        }
+        if let Some(verbosity) = self.verbosity {
+            gmsh.set_verbosity(verbosity)?;
+        }
+        if let Some(num_threads) = self.num_threads {
+            let num_threads = num_threads as f64;
+            gmsh.set_option(options::General::NumThreads, num_threads)?;
+            gmsh.set_option(options::Mesh::MaxNumThreads1D, num_threads)?;
+            gmsh.set_option(options::Mesh::MaxNumThreads2D, num_threads)?;
+            gmsh.set_option(options::Mesh::MaxNumThreads3D, num_threads)?;
+        }
+        Ok(gmsh)
+    }
+}
@@ -13,6 +13,13 @@ pub enum GmshError {
     /// One of Gmsh's "shell" methods couldn't run successfully.
     /// For example, a bad file path was given to the `open` function.
     Execution, // 1 in top-level Gmsh
+    /// Gmsh was initialized while another `Gmsh` context is still alive.
+    /// Gmsh state is global to the process, so there can only be one context at a time.
+    AlreadyInitialized,
+    /// The thread or process running Gmsh stopped before answering.
+    Disconnected,
+    /// A remote job took longer than its time limit and was stopped.
+    Timeout,
     /// Errors from the Rust/C FFI interface.
     CInterface, // Problems from the Rust/C FFI interface
     /// A function that mutates the model couldn't complete successfully.
@@ -42,6 +49,11 @@ impl Display for GmshError {
                 f,
                 "initialization error for Gmsh or an associated library is missing"
             ),
+            GmshError::AlreadyInitialized => {
+                write!(f, "Gmsh is already initialized in this process")
+            }
+            GmshError::Disconnected => write!(f, "the Gmsh worker stopped"),
+            GmshError::Timeout => write!(f, "the Gmsh job timed out"),
             _ => write!(f, "big ol error"),
         }
     }
@@ -34,8 +34,8 @@
 //!     let cl = model.add_curve_loop(&[l4, l1, -l2, l3])?;
 //!     let pl = model.add_plane_surface(cl)?;
 //!
-//!     //let physical_curve = model.add_physical_group(&[l1, l2, l4])?;
-//!     //let physical_surf = model.add_physical_group(&[pl])?;
+//!     let _physical_curve = model.add_physical_group(&[l1, l2, l4])?;
+//!     let _physical_surf = model.add_physical_group(&[pl])?;
 //!
 //!     model.generate_mesh(3)?;
 //!
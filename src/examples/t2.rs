@@ -8,8 +8,8 @@
 //!
 //! fn main() -> GmshResult<()> {
 //!
-//!     // TODO FIXME for command line args
-//!     let mut gmsh = Gmsh::initialize()?;
+//!     // pass the command-line arguments on to Gmsh, like `gmsh t2.geo -2` would
+//!     let mut gmsh = Gmsh::builder().args(std::env::args()).initialize()?;
 //!     let mut model = gmsh.create_native_model("t2")?;
 //!
 //!     // copied from t1.rs...
@@ -25,8 +25,8 @@
 //!     let cl = model.add_curve_loop(&[l4, l1, -l2, l3])?;
 //!     let pl = model.add_plane_surface(cl)?;
 //!
-//!     //let physical_curve = model.add_physical_group(&[l1, l2, l4])?;
-//!     //let physical_surf = model.add_physical_group(&[pl])?;
+//!     let _physical_curve = model.add_physical_group(&[l1, l2, l4])?;
+//!     let _physical_surf = model.add_physical_group(&[pl])?;
 //!     // ...end of copy
 //!
 //!     Ok(())
@@ -1,6 +1,7 @@
 //! Interface to the low-level `gmsh_sys` crate.
 
-use std::ffi::CString;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_void};
 
 use crate::err::{GmshError, GmshResult};
 
@@ -14,6 +15,43 @@ pub fn get_cstring(istr: &str) -> GmshResult<CString> {
     }
 }
 
+#[doc(hidden)]
+// copy an array allocated by Gmsh into a new vector, then free the original
+pub unsafe fn vec_from_raw<T: Copy>(ptr: *mut T, len: usize) -> Vec<T> {
+    if ptr.is_null() {
+        return Vec::new();
+    }
+    let vec = std::slice::from_raw_parts(ptr, len).to_vec();
+    gmsh_sys::gmshFree(ptr as *mut c_void);
+    vec
+}
+
+#[doc(hidden)]
+// copy a string allocated by Gmsh into a new Rust string, then free the original
+pub unsafe fn string_from_raw(ptr: *mut c_char) -> GmshResult<String> {
+    if ptr.is_null() {
+        return Ok(String::new());
+    }
+    let string = CStr::from_ptr(ptr).to_str().map(|s| s.to_string());
+    gmsh_sys::gmshFree(ptr as *mut c_void);
+    string.map_err(|_| GmshError::CInterface)
+}
+
+#[doc(hidden)]
+// copy an array of strings allocated by Gmsh, then free the originals
+pub unsafe fn strings_from_raw(ptr: *mut *mut c_char, len: usize) -> GmshResult<Vec<String>> {
+    if ptr.is_null() {
+        return Ok(Vec::new());
+    }
+    // free every string, even after a bad one
+    let strings: Vec<_> = std::slice::from_raw_parts(ptr, len)
+        .iter()
+        .map(|&s| string_from_raw(s))
+        .collect();
+    gmsh_sys::gmshFree(ptr as *mut c_void);
+    strings.into_iter().collect()
+}
+
 /// The set of `OpenCASCADE` kernel functions.
 pub mod occ {
 
@@ -21,6 +59,11 @@ pub mod occ {
     pub use gmsh_sys::gmshModelOccAddBox as add_box;
     pub use gmsh_sys::gmshModelOccAddSphere as add_sphere;
     pub use gmsh_sys::gmshModelOccAddTorus as add_torus;
+    pub use gmsh_sys::gmshModelOccCut as cut;
+    pub use gmsh_sys::gmshModelOccFragment as fragment;
+    pub use gmsh_sys::gmshModelOccFuse as fuse;
+    pub use gmsh_sys::gmshModelOccImportShapes as import_shapes;
+    pub use gmsh_sys::gmshModelOccIntersect as intersect;
 
     // shared functions
     pub use gmsh_sys::gmshModelOccAddCurveLoop as add_curve_loop;
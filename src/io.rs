@@ -0,0 +1,241 @@
+//! Read and write files.
+//!
+//! Gmsh picks file formats from file extensions. Geometry (`.geo`, `.step`,
+//! `.brep`, ...), meshes (`.msh`, `.unv`, ...) and post-processing views
+//! (`.pos`) can all be opened.
+//! ```no_run
+//! # use rgmsh::{Gmsh, GmshResult};
+//! # use rgmsh::io::FileFormat;
+//! # fn main() -> GmshResult<()> {
+//! let gmsh = Gmsh::initialize()?;
+//! for mut model in gmsh.open("part.step")? {
+//!     model.generate_mesh(3)?;
+//! }
+//!
+//! gmsh.write("part.msh")?;
+//! // Abaqus input, even without the usual extension
+//! gmsh.write_as("part.abaqus", FileFormat::Inp)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::interface::strings_from_raw;
+use crate::model::Model;
+use crate::{check_main_error, get_cstring, Gmsh, GmshError, GmshResult};
+use std::os::raw::{c_char, c_int};
+use std::path::{Path, PathBuf};
+
+/// File formats Gmsh can write.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FileFormat {
+    /// Gmsh mesh, `.msh`.
+    Msh,
+    /// Legacy VTK mesh, `.vtk`.
+    Vtk,
+    /// I-deas universal mesh, `.unv`.
+    Unv,
+    /// Abaqus input, `.inp`.
+    Inp,
+    /// Nastran bulk data, `.bdf`.
+    Bdf,
+    /// INRIA Medit mesh, `.mesh`.
+    Mesh,
+    /// Stereolithography surface mesh, `.stl`.
+    Stl,
+    /// SU2 mesh, `.su2`.
+    Su2,
+    /// Gmsh post-processing views, `.pos`.
+    Pos,
+    /// Built-in kernel geometry script, `.geo_unrolled`.
+    GeoUnrolled,
+    /// OpenCASCADE BRep geometry, `.brep`.
+    Brep,
+    /// STEP geometry, `.step`.
+    Step,
+    /// IGES geometry, `.iges`.
+    Iges,
+}
+
+impl FileFormat {
+    /// The file extension Gmsh recognizes the format by, without the dot.
+    pub fn extension(self) -> &'static str {
+        match self {
+            FileFormat::Msh => "msh",
+            FileFormat::Vtk => "vtk",
+            FileFormat::Unv => "unv",
+            FileFormat::Inp => "inp",
+            FileFormat::Bdf => "bdf",
+            FileFormat::Mesh => "mesh",
+            FileFormat::Stl => "stl",
+            FileFormat::Su2 => "su2",
+            FileFormat::Pos => "pos",
+            FileFormat::GeoUnrolled => "geo_unrolled",
+            FileFormat::Brep => "brep",
+            FileFormat::Step => "step",
+            FileFormat::Iges => "iges",
+        }
+    }
+
+    /// Guess a format from a file extension.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<FileFormat> {
+        let extension = path.as_ref().extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "msh" => Some(FileFormat::Msh),
+            "vtk" => Some(FileFormat::Vtk),
+            "unv" => Some(FileFormat::Unv),
+            "inp" => Some(FileFormat::Inp),
+            "bdf" | "nas" => Some(FileFormat::Bdf),
+            "mesh" => Some(FileFormat::Mesh),
+            "stl" => Some(FileFormat::Stl),
+            "su2" => Some(FileFormat::Su2),
+            "pos" => Some(FileFormat::Pos),
+            "geo_unrolled" => Some(FileFormat::GeoUnrolled),
+            "brep" => Some(FileFormat::Brep),
+            "step" | "stp" => Some(FileFormat::Step),
+            "iges" | "igs" => Some(FileFormat::Iges),
+            _ => None,
+        }
+    }
+}
+
+// Gmsh takes paths as UTF-8 strings
+pub(crate) fn path_str(path: &Path) -> GmshResult<&str> {
+    path.to_str().ok_or(GmshError::CInterface)
+}
+
+impl Gmsh {
+    /// Open a file, replacing the current model if it's empty. Returns the
+    /// opened models.
+    ///
+    /// Fails with `Execution` if the file can't be read.
+    pub fn open<P: AsRef<Path>>(&self, path: P) -> GmshResult<Vec<Model<'_>>> {
+        let c_path = get_cstring(path_str(path.as_ref())?)?;
+        let before = self.list_models()?;
+        unsafe {
+            let mut ierr: c_int = 0;
+            gmsh_sys::gmshOpen(c_path.as_ptr(), &mut ierr);
+            check_main_error!(ierr, ())?;
+        }
+        let opened: Vec<String> = self
+            .list_models()?
+            .into_iter()
+            .filter(|name| !before.contains(name))
+            .collect();
+        // the file was read into the existing current model
+        if opened.is_empty() {
+            return Ok(vec![self.current_model()?]);
+        }
+        // Gmsh makes the last opened model current
+        *self.current.borrow_mut() = opened.last().cloned();
+        opened.into_iter().map(|name| self.model(name)).collect()
+    }
+
+    /// Merge a file into the current model, e.g. a mesh or views to go with
+    /// an opened geometry.
+    pub fn merge<P: AsRef<Path>>(&self, path: P) -> GmshResult<()> {
+        let c_path = get_cstring(path_str(path.as_ref())?)?;
+        unsafe {
+            let mut ierr: c_int = 0;
+            gmsh_sys::gmshMerge(c_path.as_ptr(), &mut ierr);
+            check_main_error!(ierr, ())
+        }
+    }
+
+    /// Write the current model to a file, in the format given by its extension.
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> GmshResult<()> {
+        let c_path = get_cstring(path_str(path.as_ref())?)?;
+        unsafe {
+            let mut ierr: c_int = 0;
+            gmsh_sys::gmshWrite(c_path.as_ptr(), &mut ierr);
+            check_main_error!(ierr, ())
+        }
+    }
+
+    /// Write the current model to a file in a given format, whatever its
+    /// extension.
+    pub fn write_as<P: AsRef<Path>>(&self, path: P, format: FileFormat) -> GmshResult<()> {
+        let path = path.as_ref();
+        if FileFormat::from_path(path) == Some(format) {
+            return self.write(path);
+        }
+        // Gmsh only goes by extension, so write next to the target and rename
+        let mut staging = PathBuf::from(path);
+        staging.set_extension(format!("rgmsh.{}", format.extension()));
+        // a failed write can still leave a partial file behind
+        if let Err(e) = self.write(&staging) {
+            let _ = std::fs::remove_file(&staging);
+            return Err(e);
+        }
+        std::fs::rename(&staging, path).map_err(|_| {
+            let _ = std::fs::remove_file(&staging);
+            GmshError::Execution
+        })
+    }
+
+    /// Remove all models and views.
+    pub fn clear(&mut self) -> GmshResult<()> {
+        self.clear_shared()
+    }
+
+    // Clear through a shared borrow, for callers that drop their model
+    // handles themselves, e.g. the remote server
+    pub(crate) fn clear_shared(&self) -> GmshResult<()> {
+        unsafe {
+            let mut ierr: c_int = 0;
+            gmsh_sys::gmshClear(&mut ierr);
+            check_main_error!(ierr, ())?;
+        }
+        self.unsynchronized.borrow_mut().clear();
+        *self.current.borrow_mut() = None;
+        Ok(())
+    }
+
+    /// Get the names of all models.
+    pub fn list_models(&self) -> GmshResult<Vec<String>> {
+        unsafe {
+            let mut ierr: c_int = 0;
+            let mut names: *mut *mut c_char = std::ptr::null_mut();
+            let mut names_n: usize = 0;
+            gmsh_sys::gmshModelList(&mut names, &mut names_n, &mut ierr);
+            let names = strings_from_raw(names, names_n)?;
+            check_main_error!(ierr, names)
+        }
+    }
+
+    /// Get the name of the current model.
+    ///
+    /// Gmsh 4.4.1 can't report the current model, so it's tracked as models
+    /// are created, opened and set current. After a model is removed or
+    /// everything is cleared, the last model in `list_models` is made current.
+    pub fn current_model_name(&self) -> GmshResult<String> {
+        if let Some(name) = self.current.borrow().clone() {
+            return Ok(name);
+        }
+        let name = self.list_models()?.pop().ok_or(GmshError::ModelLookup)?;
+        let c_name = get_cstring(&name)?;
+        unsafe {
+            let mut ierr: c_int = 0;
+            gmsh_sys::gmshModelSetCurrent(c_name.as_ptr(), &mut ierr);
+            check_main_error!(ierr, ())?;
+        }
+        *self.current.borrow_mut() = Some(name.clone());
+        Ok(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_from_extensions() {
+        assert_eq!(FileFormat::from_path("part.STEP"), Some(FileFormat::Step));
+        assert_eq!(FileFormat::from_path("a/b/mesh.msh"), Some(FileFormat::Msh));
+        assert_eq!(
+            FileFormat::from_path("model.geo_unrolled"),
+            Some(FileFormat::GeoUnrolled)
+        );
+        assert_eq!(FileFormat::from_path("notes.txt"), None);
+        assert_eq!(FileFormat::from_path("no_extension"), None);
+    }
+}
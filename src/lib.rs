@@ -35,7 +35,10 @@
 // todo figure out where this import belongs
 extern crate gmsh_sys;
 
+use std::cell::RefCell;
+use std::marker::PhantomData;
 use std::os::raw::{c_char, c_int, c_void};
+use std::sync::atomic::{AtomicBool, Ordering};
 
 pub mod err;
 #[doc(inline)]
@@ -45,25 +48,60 @@ pub mod fltk;
 
 pub mod interface;
 use interface::get_cstring;
-use std::ffi::{CStr, CString};
+use std::ffi::CStr;
 
 pub mod model;
 #[doc(inline)]
-pub use model::{GeoModel, OccModel};
+pub use model::{GeoModel, Model, OccModel};
+use model::Kernel;
+
+pub mod view;
+#[doc(inline)]
+pub use view::ViewTag;
+
+pub mod plugin;
+
+pub mod options;
+use options::Color;
+
+pub mod logger;
+
+pub mod trace;
+
+pub mod testing;
+
+pub mod service;
+
+pub mod remote;
+
+pub mod io;
+
+pub mod builder;
+#[doc(inline)]
+pub use builder::GmshBuilder;
 
 pub mod examples;
 
 // mes
 struct FieldTag(i64);
 
-// post-processing
-struct ViewTag(i64);
-
 /// Gmsh context object
+///
+/// Gmsh keeps its state in process-wide globals, so only one context can be
+/// alive at a time. The context isn't `Send` or `Sync`: Gmsh must be used
+/// from the thread that initialized it.
 pub struct Gmsh {
-    // todo add a log for used-model names
+    // models with kernel changes that haven't been synchronized
+    unsynchronized: RefCell<Vec<(String, Kernel)>>,
+    // the current model, which the Gmsh 4.4.1 API can't report
+    current: RefCell<Option<String>>,
+    // raw pointers are neither Send nor Sync
+    _not_send: PhantomData<*const ()>,
 }
 
+// Set while a Gmsh context is alive
+static INITIALIZED: AtomicBool = AtomicBool::new(false);
+
 // gmsh {
 //
 //     model {
@@ -89,52 +127,69 @@ pub struct Gmsh {
 impl Gmsh {
     /// Create the main Gmsh object. All API functions are provided through this
     /// object.
-    // think about std::sync::Once object ?
+    ///
+    /// Fails with `AlreadyInitialized` if another context is still alive. Use
+    /// [`Gmsh::builder`] to pass command-line arguments or other settings.
     pub fn initialize() -> GmshResult<Self> {
-        // println!("opening Gmsh...");
-
-        unsafe {
-            let mut ierr: c_int = 0;
-            let gmsh_name = CString::new("gmsh").unwrap();
-            let name_arg = gmsh_name.into_raw();
-            gmsh_sys::gmshInitialize(
-                // argc
-                1 as c_int,
-                // argv
-                [name_arg].as_mut_ptr(),
-                // don't read configuration files
-                0,
-                // error out-parameter
-                &mut ierr,
-            );
+        GmshBuilder::new().initialize()
+    }
 
-            // free name_arg
-            let _ = CString::from_raw(name_arg);
-
-            if ierr == 0 {
-                // send logs to terminal
-                let mut gmsh = Self {};
-                gmsh.set_number_option("General.Terminal", 1.)?;
-                //println!("Gmsh {}", gmsh.get_string_option("General.Version")?);
-                Ok(gmsh)
-            } else {
-                Err(GmshError::Initialization)
-            }
-        }
+    /// Configure Gmsh before initializing it.
+    pub fn builder() -> GmshBuilder {
+        GmshBuilder::new()
     }
 
     /// Make a new model using the built-in Gmsh geometry kernel
-    pub fn create_native_model(&self, name: &'static str) -> GmshResult<GeoModel> {
-        //  println!("added built-in geometry model {} ", name);
+    pub fn create_native_model<N: Into<String>>(&self, name: N) -> GmshResult<GeoModel> {
         GeoModel::create(self, name)
     }
 
     /// Make a new model using the OpenCASCADE geometry kernel
-    pub fn create_occ_model(&self, name: &'static str) -> GmshResult<OccModel> {
-        //  println!("added OpenCASCADE model {} ", name);
+    pub fn create_occ_model<N: Into<String>>(&self, name: N) -> GmshResult<OccModel> {
         OccModel::create(self, name)
     }
 
+    /// Get an existing model by name, to query or mesh it.
+    pub fn model<N: Into<String>>(&self, name: N) -> GmshResult<Model<'_>> {
+        Model::by_name(self, name)
+    }
+
+    /// Get the current model, to query or mesh it.
+    pub fn current_model(&self) -> GmshResult<Model<'_>> {
+        Model::by_name(self, self.current_model_name()?)
+    }
+
+    /// Get an existing model by name, using the built-in geometry kernel.
+    /// ```no_run
+    /// # use rgmsh::{Gmsh, GmshResult};
+    /// # fn main() -> GmshResult<()> {
+    /// let gmsh = Gmsh::initialize()?;
+    /// for model in gmsh.open("t1.geo")? {
+    ///     let mut geom = gmsh.native_model(&model.name)?;
+    ///     geom.generate_mesh(2)?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn native_model<N: Into<String>>(&self, name: N) -> GmshResult<GeoModel<'_>> {
+        GeoModel::by_name(self, name)
+    }
+
+    /// Get an existing model by name, using the OpenCASCADE geometry kernel.
+    pub fn occ_model<N: Into<String>>(&self, name: N) -> GmshResult<OccModel<'_>> {
+        OccModel::by_name(self, name)
+    }
+
+    /// Get the current model, using the built-in geometry kernel.
+    pub fn current_native_model(&self) -> GmshResult<GeoModel<'_>> {
+        GeoModel::by_name(self, self.current_model_name()?)
+    }
+
+    /// Get the current model, using the OpenCASCADE geometry kernel.
+    pub fn current_occ_model(&self) -> GmshResult<OccModel<'_>> {
+        OccModel::by_name(self, self.current_model_name()?)
+    }
+
     /// Get a numeric option.
     pub fn get_number_option(&self, name: &str) -> GmshResult<f64> {
         let c_name = get_cstring(name)?;
@@ -147,7 +202,7 @@ impl Gmsh {
     }
 
     /// Set a numeric option.
-    pub fn set_number_option(&mut self, name: &str, value: f64) -> GmshResult<()> {
+    pub fn set_number_option(&self, name: &str, value: f64) -> GmshResult<()> {
         let c_name = get_cstring(name)?;
         let mut ierr: c_int = 0;
         unsafe {
@@ -181,7 +236,7 @@ impl Gmsh {
     }
 
     /// Set a string option.
-    pub fn set_string_option(&mut self, name: &str, value: &str) -> GmshResult<()> {
+    pub fn set_string_option(&self, name: &str, value: &str) -> GmshResult<()> {
         let c_name = get_cstring(name)?;
         let c_value = get_cstring(value)?;
         let mut ierr: c_int = 0;
@@ -190,31 +245,79 @@ impl Gmsh {
         }
         check_option_error!(ierr, ())
     }
+
+    /// Get a color option.
+    pub fn get_color_option(&self, name: &str) -> GmshResult<Color> {
+        let c_name = get_cstring(name)?;
+        let (mut r, mut g, mut b, mut a): (c_int, c_int, c_int, c_int) = (0, 0, 0, 0);
+        let mut ierr: c_int = 0;
+        unsafe {
+            gmsh_sys::gmshOptionGetColor(
+                c_name.as_ptr(),
+                &mut r,
+                &mut g,
+                &mut b,
+                &mut a,
+                &mut ierr,
+            );
+        }
+        check_option_error!(
+            ierr,
+            Color {
+                r: r as u8,
+                g: g as u8,
+                b: b as u8,
+                a: a as u8,
+            }
+        )
+    }
+
+    /// Set a color option.
+    pub fn set_color_option(&self, name: &str, color: Color) -> GmshResult<()> {
+        let c_name = get_cstring(name)?;
+        let mut ierr: c_int = 0;
+        unsafe {
+            gmsh_sys::gmshOptionSetColor(
+                c_name.as_ptr(),
+                color.r.into(),
+                color.g.into(),
+                color.b.into(),
+                color.a.into(),
+                &mut ierr,
+            );
+        }
+        check_option_error!(ierr, ())
+    }
 }
 
 impl Drop for Gmsh {
     fn drop(&mut self) {
         // println!("finalizing Gmsh...");
+        let _ = logger::forward();
+        logger::stop();
         unsafe {
             // don't check finalization errors
             let mut ierr: c_int = 0;
             gmsh_sys::gmshFinalize(&mut ierr);
         }
+        INITIALIZED.store(false, Ordering::SeqCst);
     }
 }
 
-/// Tests must be run with `--test-threads=1` since they depend on the shared Gmsh state.
+/// Tests take turns with the shared Gmsh state through `serial_gmsh`.
 #[cfg(test)]
 mod tests {
 
     // import all names from the outer scope
     use super::*;
     use crate::model::*;
+    use crate::service::GmshService;
+    use crate::testing::{self, serial_gmsh};
 
     /// Check multiple models can be made and follow the same numbering rules
     #[test]
     pub fn multiple_models() -> GmshResult<()> {
-        let gmsh = Gmsh::initialize()?;
+        let gmsh = serial_gmsh()?;
         let mut occ_geom = gmsh.create_occ_model("box")?;
         let p1 = occ_geom.add_point(0., 0., 0.)?;
 
@@ -228,9 +331,330 @@ mod tests {
         Ok(())
     }
 
+    /// Check existing models can be found by name, including generated names
+    #[test]
+    pub fn named_models() -> GmshResult<()> {
+        let gmsh = serial_gmsh()?;
+        for i in 0..3 {
+            gmsh.create_native_model(format!("part_{}", i))?;
+        }
+        assert!(gmsh.list_models()?.contains(&"part_1".to_string()));
+        assert_eq!(gmsh.current_model_name()?, "part_2");
+
+        let mut geom = gmsh.occ_model("part_1")?;
+        geom.add_point(0., 0., 0.)?;
+        assert_eq!(gmsh.current_native_model()?.name, "part_1");
+
+        match gmsh.native_model("missing") {
+            Err(GmshError::ModelLookup) => Ok(()),
+            _ => panic!(),
+        }
+    }
+
+    /// Check kernel changes are synchronized into the model only when needed
+    #[test]
+    pub fn tracks_synchronization() -> GmshResult<()> {
+        let gmsh = serial_gmsh()?;
+        let mut geom = gmsh.create_occ_model("model")?;
+        assert!(geom.is_synchronized());
+
+        geom.add_box((0., 0., 0.), (1., 1., 1.))?;
+        assert!(!geom.is_synchronized());
+        geom.get_entities(Some(3))?;
+        assert!(geom.is_synchronized());
+
+        geom.add_sphere((2., 0., 0.), 0.5)?;
+        let mut model = geom.into_model()?;
+        assert!(model.is_synchronized());
+        model.generate_mesh(3)
+    }
+
+    /// Check bad discrete entities are rejected before anything is added
+    #[test]
+    pub fn discrete_entity_dims() -> GmshResult<()> {
+        let gmsh = serial_gmsh()?;
+        let mut geom = gmsh.create_native_model("model")?;
+        let p1 = geom.add_point(0., 0., 0.)?;
+        let p2 = geom.add_point(1., 0., 0.)?;
+        let l1 = geom.add_line(p1, p2)?;
+
+        let no_boundary: &[PointTag] = &[];
+        for &dim in &[-1, 4] {
+            match geom.add_discrete_entity(dim, no_boundary) {
+                Err(GmshError::ModelBadInput) => (),
+                _ => panic!(),
+            }
+        }
+        geom.add_discrete_entity(2, &[l1])?;
+        match geom.add_discrete_entity(3, &[l1]) {
+            Err(GmshError::ModelBadInput) => Ok(()),
+            _ => panic!(),
+        }
+    }
+
+    /// Check node orderings are pushed back into Gmsh, and bad ones rejected
+    #[test]
+    pub fn reorder_nodes() -> GmshResult<()> {
+        let gmsh = serial_gmsh()?;
+        let mut geom = gmsh.create_native_model("model")?;
+        let p1 = geom.add_point(0., 0., 0.)?;
+        let p2 = geom.add_point(1., 0., 0.)?;
+        let line = geom.add_line(p1, p2)?;
+        geom.generate_mesh(1)?;
+
+        let before = geom.get_nodes(line, true)?;
+        let mut ordering = before.tags.clone();
+        ordering.reverse();
+        geom.reorder_nodes(&ordering)?;
+        let after = geom.get_nodes(line, true)?;
+        for (old_tag, coords) in before.tags.iter().zip(before.coords.chunks_exact(3)) {
+            let new_tag = ordering.iter().position(|t| t == old_tag).unwrap() + 1;
+            let i = after.tags.iter().position(|&t| t == new_tag).unwrap();
+            assert!(&after.coords[3 * i..3 * i + 3] == coords);
+        }
+
+        let max_tag = after.tags.iter().cloned().max().unwrap_or(0);
+        for bad_ordering in &[vec![1, 1], vec![max_tag + 1]] {
+            match geom.reorder_nodes(bad_ordering) {
+                Err(GmshError::ModelBadInput) => (),
+                _ => panic!(),
+            }
+        }
+        Ok(())
+    }
+
+    /// Check only groups made by the homology computation are returned as chains
+    #[test]
+    pub fn homology_chains_are_new_groups() -> GmshResult<()> {
+        let gmsh = serial_gmsh()?;
+        let mut geom = gmsh.create_occ_model("model")?;
+        let torus = geom.add_torus((0., 0., 0.), (1., 0.4))?;
+        let domain = geom.add_physical_group(&[torus])?;
+        // a user group that looks like a chain
+        geom.set_physical_name(domain, "H_user")?;
+
+        geom.add_homology_request(&HomologyRequest::cohomology(&[domain]).with_dims(&[1]))?;
+        let chains = geom.generate_mesh_with_homology(3)?;
+        assert!(chains.iter().all(|&(_, group)| group != domain));
+        Ok(())
+    }
+
+    /// Check uneven view data is rejected before it reaches Gmsh
+    #[test]
+    pub fn view_data_checks() -> GmshResult<()> {
+        use crate::view::ViewDataType;
+        let gmsh = serial_gmsh()?;
+        let geom = gmsh.create_native_model("model")?;
+        let view = gmsh.add_view("data")?;
+        let bad_data: &[(&[usize], &[f64], i32)] = &[
+            (&[1], &[], 1),
+            (&[], &[1.], 1),
+            (&[1, 2], &[1., 2., 3.], 1),
+            (&[1], &[1., 2.], 3),
+            (&[1], &[1.], 0),
+        ];
+        for &(tags, data, num_components) in bad_data {
+            let node_data = ViewDataType::NodeData;
+            let added = gmsh.add_model_data(view, 0, &geom.name, node_data, tags, data, 0., num_components);
+            match added {
+                Err(GmshError::ModelBadInput) => (),
+                _ => panic!(),
+            }
+        }
+        Ok(())
+    }
+
+    /// Check a written mesh can be opened, merged and cleared again
+    #[test]
+    pub fn write_open_merge_clear() -> GmshResult<()> {
+        use crate::io::FileFormat;
+
+        let mut gmsh = serial_gmsh()?;
+        let dir = std::env::temp_dir().join(format!("rgmsh-io-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).map_err(|_| GmshError::Execution)?;
+        let mesh = dir.join("box.msh");
+        {
+            let mut geom = gmsh.create_occ_model("box")?;
+            geom.add_box((0., 0., 0.), (1., 1., 1.))?;
+            geom.generate_mesh(3)?;
+        }
+        gmsh.write(&mesh)?;
+        // the extension doesn't decide the format, and nothing is left behind
+        gmsh.write_as(dir.join("box.out"), FileFormat::Msh)?;
+        assert!(dir.join("box.out").exists());
+        assert!(!dir.join("box.rgmsh.msh").exists());
+        gmsh.clear()?;
+
+        {
+            let models = gmsh.open(&mesh)?;
+            assert_eq!(models.len(), 1);
+            let volumes = models[0].get_entities(Some(3))?;
+            assert_eq!(volumes.len(), 1);
+            let num_nodes = models[0].get_nodes(volumes[0], true)?.tags.len();
+            assert!(num_nodes > 0);
+            gmsh.merge(&mesh)?;
+        }
+        gmsh.clear()?;
+        assert!(gmsh.model("box").is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+        Ok(())
+    }
+
+    /// Check changes made through one handle are synchronized through another
+    #[test]
+    pub fn shared_synchronization() -> GmshResult<()> {
+        let gmsh = serial_gmsh()?;
+        {
+            let mut geom = gmsh.create_occ_model("model")?;
+            geom.add_box((0., 0., 0.), (1., 1., 1.))?;
+        }
+        let model = gmsh.model("model")?;
+        assert!(!model.is_synchronized());
+        assert_eq!(model.get_entities(Some(3))?.len(), 1);
+        assert!(model.is_synchronized());
+        Ok(())
+    }
+
+    #[test]
+    pub fn single_context() -> GmshResult<()> {
+        let gmsh = serial_gmsh()?;
+        match Gmsh::initialize() {
+            Err(GmshError::AlreadyInitialized) => (),
+            _ => panic!(),
+        }
+        // the context still works
+        gmsh.create_native_model("model")?;
+        Ok(())
+    }
+
+    #[test]
+    pub fn service_runs_jobs() -> GmshResult<()> {
+        let _lock = testing::lock();
+        let service = GmshService::start()?;
+        let handle = service.handle();
+
+        let worker = std::thread::spawn(move || {
+            handle.run(|gmsh| -> GmshResult<PointTag> {
+                let mut geom = gmsh.create_native_model("model")?;
+                geom.add_point(0., 0., 0.)
+            })
+        });
+        let point = worker.join().unwrap()??;
+
+        let same_point = service.handle().run(move |gmsh| -> GmshResult<bool> {
+            let mut geom = gmsh.create_native_model("model")?;
+            Ok(point == geom.add_point(1., 1., 1.)?)
+        })??;
+        assert!(same_point);
+        Ok(())
+    }
+
+    #[test]
+    pub fn service_runs_async_jobs() -> GmshResult<()> {
+        use std::future::Future;
+        use std::sync::Arc;
+        use std::task::{Context, Poll, Wake};
+
+        // wake the test thread when the job is done
+        struct Unpark(std::thread::Thread);
+        impl Wake for Unpark {
+            fn wake(self: Arc<Self>) {
+                self.0.unpark();
+            }
+        }
+        fn block_on<F: Future>(future: F) -> F::Output {
+            let waker = Arc::new(Unpark(std::thread::current())).into();
+            let mut cx = Context::from_waker(&waker);
+            let mut future = Box::pin(future);
+            loop {
+                match future.as_mut().poll(&mut cx) {
+                    Poll::Ready(output) => return output,
+                    Poll::Pending => std::thread::park(),
+                }
+            }
+        }
+
+        let _lock = testing::lock();
+        let service = GmshService::start()?;
+        let handle = service.handle();
+
+        let (a, b) = block_on(handle.run_async(|gmsh| -> GmshResult<_> {
+            let mut geom = gmsh.create_occ_model("box")?;
+            let a = geom.add_box((0., 0., 0.), (1., 1., 1.))?;
+            let b = geom.add_box((0.5, 0., 0.), (1., 1., 1.))?;
+            Ok((a, b))
+        }))??;
+        let fused = block_on(handle.boolean(
+            "box",
+            BooleanOp::Fuse,
+            vec![a.into()],
+            vec![b.into()],
+        ))?;
+        assert_eq!(fused.len(), 1);
+        block_on(handle.generate_mesh("box", 3))?;
+        // the box was added through a handle that's gone, and still gets meshed
+        let num_nodes = handle.run(|gmsh| -> GmshResult<usize> {
+            let model = gmsh.model("box")?;
+            let volume = model.get_entities(Some(3))?[0];
+            Ok(model.get_nodes(volume, true)?.tags.len())
+        })??;
+        assert!(num_nodes > 0);
+
+        // the worker is gone, so queued jobs never run
+        drop(service);
+        match block_on(handle.run_async(|_| ())) {
+            Err(GmshError::Disconnected) => (),
+            _ => panic!(),
+        }
+        Ok(())
+    }
+
+    #[test]
+    pub fn remote_server_answers() -> GmshResult<()> {
+        let _lock = testing::lock();
+        let requests = "new_model occ box\n\
+                        add_box box 0 0 0 1 1 1\n\
+                        add_box missing 0 0 0 1 1 1\n\
+                        bad\\x box\n\
+                        set_number_option Mesh.Algorithm 5\n\
+                        get_number_option Mesh.Algorithm\n\
+                        quit\n";
+        let mut responses = Vec::new();
+        crate::remote::server::serve(requests.as_bytes(), &mut responses)?;
+
+        let responses = String::from_utf8(responses).unwrap();
+        let lines: Vec<&str> = responses.lines().collect();
+        // a malformed command is answered, and the server keeps going
+        assert_eq!(
+            lines,
+            [
+                "ok",
+                "ok",
+                "ok 1",
+                "err ModelLookup",
+                "err CInterface",
+                "ok",
+                "ok 5"
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    pub fn builder_settings() -> GmshResult<()> {
+        let _lock = testing::lock();
+        let gmsh = Gmsh::builder()
+            .args(vec!["gmsh", "-v", "2"])
+            .num_threads(3)
+            .initialize()?;
+        assert!(3. == gmsh.get_option(options::General::NumThreads)?);
+        assert!(2. == gmsh.get_option(options::General::Verbosity)?);
+        Ok(())
+    }
+
     #[test]
     pub fn catch_unknown_options() -> GmshResult<()> {
-        let mut gmsh = Gmsh::initialize()?;
+        let gmsh = serial_gmsh()?;
         let geom = gmsh.create_occ_model("model")?;
         let bad_opt = "Bad.Option";
 
@@ -258,7 +682,7 @@ mod tests {
 
     #[test]
     pub fn set_and_return_opts() -> GmshResult<()> {
-        let mut gmsh = Gmsh::initialize()?;
+        let gmsh = serial_gmsh()?;
         let geom = gmsh.create_occ_model("model")?;
 
         let opt = "Solver.Name0";
@@ -273,4 +697,44 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    pub fn typed_options() -> GmshResult<()> {
+        let gmsh = serial_gmsh()?;
+
+        gmsh.set_option(options::Mesh::CharacteristicLengthMax, 0.25)?;
+        assert!(0.25 == gmsh.get_option(options::Mesh::CharacteristicLengthMax)?);
+
+        gmsh.reset_option(options::Mesh::CharacteristicLengthMax)?;
+        assert!(1e22 == gmsh.get_option(options::Mesh::CharacteristicLengthMax)?);
+
+        assert!("GetDP" == gmsh.get_option(options::Solver::Name0)?);
+
+        let red = Color::rgb(255, 0, 0);
+        gmsh.set_option(options::General::BackgroundColor, red)?;
+        assert!(red == gmsh.get_option(options::General::BackgroundColor)?);
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn scoped_options_restore() -> GmshResult<()> {
+        let mut gmsh = serial_gmsh()?;
+        let opt = "Mesh.Algorithm";
+        let original = gmsh.get_number_option(opt)?;
+
+        {
+            let mut guard = gmsh.scoped_options();
+            guard.set_number_option(opt, 5.)?;
+            guard.set_number_option(opt, 1.)?;
+            assert!(1. == guard.get_number_option(opt)?);
+        }
+        assert!(original == gmsh.get_number_option(opt)?);
+
+        let inside = gmsh.with_options(&[(opt, 5.)], |gmsh| gmsh.get_number_option(opt))??;
+        assert!(5. == inside);
+        assert!(original == gmsh.get_number_option(opt)?);
+
+        Ok(())
+    }
 }
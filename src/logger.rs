@@ -0,0 +1,181 @@
+//! Forward Gmsh messages to the [`log`](https://docs.rs/log) crate.
+//!
+//! Gmsh messages are logged with the `"gmsh"` target, at the level matching
+//! their kind: errors, warnings, info and debug messages. Gmsh doesn't call
+//! back when it logs something, so messages are forwarded at checkpoints:
+//! after meshing, when [`Gmsh::forward_log`] is called and when Gmsh is
+//! finalized.
+//! ```no_run
+//! # use rgmsh::{Gmsh, GmshResult};
+//! # fn main() -> GmshResult<()> {
+//! // any `log` backend works, e.g. env_logger with RUST_LOG=gmsh=warn
+//! let mut gmsh = Gmsh::initialize()?;
+//! let mut geom = gmsh.create_occ_model("model")?;
+//! geom.add_box((0., 0., 0.), (1., 1., 1.))?;
+//! // meshing warnings are forwarded when meshing ends
+//! geom.generate_mesh(3)?;
+//! # Ok(())
+//! # }
+//! ```
+//! To print messages on the terminal instead, set the `General.Terminal` option.
+//!
+//! How much Gmsh says is set with [`Gmsh::set_verbosity`], which also controls
+//! rgmsh's own messages.
+
+use crate::interface::strings_from_raw;
+use crate::{check_main_error, options, Gmsh, GmshError, GmshResult};
+use log::Level;
+use std::os::raw::{c_char, c_int};
+use std::sync::atomic::{AtomicI32, AtomicUsize, Ordering};
+
+/// The target of forwarded Gmsh messages.
+pub const LOG_TARGET: &str = "gmsh";
+
+// How many messages of the Gmsh log were forwarded already
+static FORWARDED: AtomicUsize = AtomicUsize::new(0);
+
+/// How much Gmsh and rgmsh log (`General.Verbosity`).
+///
+/// Each level includes the levels below it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    /// Only fatal errors.
+    Silent = 0,
+    /// Errors.
+    Errors = 1,
+    /// Warnings.
+    Warnings = 2,
+    /// Direct output from the meshers.
+    Direct = 3,
+    /// Information messages.
+    Info = 4,
+    /// Status messages, the Gmsh default.
+    Status = 5,
+    /// Debug messages, including rgmsh's API call trace.
+    Debug = 99,
+}
+
+impl Verbosity {
+    fn from_level(level: i32) -> Verbosity {
+        match level {
+            l if l <= 0 => Verbosity::Silent,
+            1 => Verbosity::Errors,
+            2 => Verbosity::Warnings,
+            3 => Verbosity::Direct,
+            4 => Verbosity::Info,
+            5..=98 => Verbosity::Status,
+            _ => Verbosity::Debug,
+        }
+    }
+}
+
+static VERBOSITY: AtomicI32 = AtomicI32::new(Verbosity::Status as i32);
+
+// Check if rgmsh messages at this verbosity should be logged
+pub(crate) fn logs(verbosity: Verbosity) -> bool {
+    VERBOSITY.load(Ordering::Relaxed) >= verbosity as i32
+}
+
+// Start recording Gmsh messages
+pub(crate) fn start() -> GmshResult<()> {
+    FORWARDED.store(0, Ordering::SeqCst);
+    unsafe {
+        let mut ierr: c_int = 0;
+        gmsh_sys::gmshLoggerStart(&mut ierr);
+        check_main_error!(ierr, ())
+    }
+}
+
+// Stop recording Gmsh messages
+pub(crate) fn stop() {
+    unsafe {
+        // don't check errors, this runs on shutdown
+        let mut ierr: c_int = 0;
+        gmsh_sys::gmshLoggerStop(&mut ierr);
+    }
+}
+
+// Every message recorded since the logger started
+fn get() -> GmshResult<Vec<String>> {
+    unsafe {
+        let mut ierr: c_int = 0;
+        let mut log: *mut *mut c_char = std::ptr::null_mut();
+        let mut log_n: usize = 0;
+        gmsh_sys::gmshLoggerGet(&mut log, &mut log_n, &mut ierr);
+        let messages = strings_from_raw(log, log_n)?;
+        check_main_error!(ierr, messages)
+    }
+}
+
+// Send the messages recorded since the last call to the `log` crate
+pub(crate) fn forward() -> GmshResult<()> {
+    let messages = get()?;
+    let mut forwarded = FORWARDED.load(Ordering::SeqCst);
+    // the log was cleared behind our back
+    if forwarded > messages.len() {
+        forwarded = 0;
+    }
+    for message in &messages[forwarded..] {
+        let (level, text) = parse_message(message);
+        log::log!(target: LOG_TARGET, level, "{}", text);
+    }
+    FORWARDED.store(messages.len(), Ordering::SeqCst);
+    Ok(())
+}
+
+// Split a Gmsh message like "Warning: ..." into a level and its text
+fn parse_message(message: &str) -> (Level, &str) {
+    if let Some(split) = message.find(':') {
+        let level = match message[..split].trim() {
+            "Error" => Some(Level::Error),
+            "Warning" => Some(Level::Warn),
+            "Info" => Some(Level::Info),
+            "Debug" => Some(Level::Debug),
+            "Progress" => Some(Level::Trace),
+            _ => None,
+        };
+        if let Some(level) = level {
+            return (level, message[split + 1..].trim_start());
+        }
+    }
+    (Level::Info, message)
+}
+
+impl Gmsh {
+    /// Set how much Gmsh and rgmsh log.
+    pub fn set_verbosity(&mut self, verbosity: Verbosity) -> GmshResult<()> {
+        self.set_option(options::General::Verbosity, verbosity as i32)?;
+        VERBOSITY.store(verbosity as i32, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Get how much Gmsh and rgmsh log.
+    pub fn verbosity(&self) -> GmshResult<Verbosity> {
+        let level = self.get_option(options::General::Verbosity)? as i32;
+        Ok(Verbosity::from_level(level))
+    }
+
+    /// Send new Gmsh messages to the `log` crate.
+    pub fn forward_log(&self) -> GmshResult<()> {
+        forward()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn message_levels() {
+        assert_eq!(
+            parse_message("Warning: bad curve"),
+            (Level::Warn, "bad curve")
+        );
+        assert_eq!(
+            parse_message("Info    : Meshing 1D..."),
+            (Level::Info, "Meshing 1D...")
+        );
+        assert_eq!(parse_message("Error: no model"), (Level::Error, "no model"));
+        assert_eq!(parse_message("Ratio: 1:2"), (Level::Info, "Ratio: 1:2"));
+    }
+}
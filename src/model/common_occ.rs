@@ -6,7 +6,7 @@ fn add_point_gen(
      coords: (f64, f64, f64),
      mesh_size: Option<f64>,
  ) -> GmshResult<PointTag> {
-     self.set_current()?;
+     self.modify()?;
 
      let (x, y, z) = coords;
 
@@ -24,41 +24,47 @@ fn add_point_gen(
 /// Add a point to the model by specifying its coordinates.
 #[must_use]
 pub fn add_point(&mut self, x: f64, y: f64, z: f64) -> GmshResult<PointTag> {
-    println!("added basic point");
-    self.add_point_gen((x, y, z), None)
+    let tag = self.add_point_gen((x, y, z), None);
+    trace_call!(self.name, "add_point({:?}, {:?}, {:?})", x, y, z => tag);
+    tag
 }
 
 /// Add a point to the model and specify a target mesh size `lc` there.
 #[must_use]
 pub fn add_point_with_lc(&mut self, x: f64, y: f64, z: f64, lc: f64) -> GmshResult<PointTag> {
-    println!("added point with lc");
-    self.add_point_gen((x, y, z), Some(lc))
+    let tag = self.add_point_gen((x, y, z), Some(lc));
+    trace_call!(self.name, "add_point_with_lc({:?}, {:?}, {:?}, {:?})", x, y, z, lc => tag);
+    tag
 }
 
 
 /// Delete a point from the Gmsh model.
 // todo: Genericize this for all GeometryTags
 pub fn remove_point(&mut self, p: PointTag) -> GmshResult<()> {
-    self.set_current()?;
+    self.modify()?;
     let raw_tag = p.0;
     unsafe {
         let vec_len = 1;
         let is_recursive = 0;
         let mut ierr: c_int = 0;
         factory::remove_point([raw_tag].as_mut_ptr(), vec_len, is_recursive, &mut ierr);
-        check_model_error!(ierr, ())
+        let result = check_model_error!(ierr, ());
+        trace_call!(self.name, "remove_point({:?})", p => result);
+        result
     }
 }
 
 /// Add a straight line between two points.
 #[must_use]
 pub fn add_line(&mut self, p1: PointTag, p2: PointTag) -> GmshResult<CurveTag> {
-    self.set_current()?;
+    self.modify()?;
     let auto_number = -1;
     unsafe {
         let mut ierr: c_int = 0;
         let out_tag = factory::add_line(p1.to_raw(), p2.to_raw(), auto_number, &mut ierr);
-        check_model_error!(ierr, CurveTag(out_tag))
+        let result = check_model_error!(ierr, CurveTag(out_tag));
+        trace_call!(self.name, "add_line({:?}, {:?})", p1, p2 => result);
+        result
     }
 }
 
@@ -66,13 +72,15 @@ pub fn add_line(&mut self, p1: PointTag, p2: PointTag) -> GmshResult<CurveTag> {
 /// Add a curve loop from a closed set of curves.
 #[must_use]
 pub fn add_curve_loop(&mut self, curves: &[CurveTag]) -> GmshResult<WireTag> {
-    self.set_current()?;
+    self.modify()?;
     let mut raw_tags: Vec<_> = curves.iter().map(|c| c.to_raw()).collect();
     let auto_number = -1;
     unsafe {
         let mut ierr: c_int = 0;
         let out_tag = factory::add_curve_loop(raw_tags.as_mut_ptr(), raw_tags.len() as usize, auto_number, &mut ierr);
-        check_model_error!(ierr, WireTag(out_tag))
+        let result = check_model_error!(ierr, WireTag(out_tag));
+        trace_call!(self.name, "add_curve_loop({:?})", curves => result);
+        result
     }
 }
 
@@ -82,17 +90,6 @@ pub fn add_plane_surface(&mut self, boundary: WireTag) -> GmshResult<SurfaceTag>
     self.add_plane_surface_gen(&[boundary])
 }
 
-/// A certain operation that only works for curves and surfaces.
-pub fn curve_or_surface_op<T: Into<CurveOrSurface>>(&mut self, gen_entity: T) {
-    let entity = gen_entity.into();
-    match entity {
-        CurveOrSurface::Curve(CurveTag(ct)) => println!("Curve with tag {:?}", ct),
-        CurveOrSurface::Surface(SurfaceTag(ct)) => {
-            println!("Surface with tag {:?}", ct)
-        }
-    }
-}
-
 /// Add a surface with holes.
 #[must_use]
 pub fn add_plane_surface_with_holes(&mut self, boundary: WireTag, holes: &[WireTag]) -> GmshResult<SurfaceTag> {
@@ -101,13 +98,15 @@ pub fn add_plane_surface_with_holes(&mut self, boundary: WireTag, holes: &[WireT
 
 #[doc(hidden)]
 fn add_plane_surface_gen(&mut self, curves: &[WireTag]) -> GmshResult<SurfaceTag> {
-    self.set_current()?;
+    self.modify()?;
     let mut raw_tags: Vec<_> = curves.iter().map(|c| c.to_raw()).collect();
     let auto_number = -1;
     unsafe {
         let mut ierr: c_int = 0;
         let out_tag = factory::add_plane_surface(raw_tags.as_mut_ptr(), raw_tags.len() as usize, auto_number, &mut ierr);
-        check_model_error!(ierr, SurfaceTag(out_tag))
+        let result = check_model_error!(ierr, SurfaceTag(out_tag));
+        trace_call!(self.name, "add_plane_surface({:?})", curves => result);
+        result
     }
 }
 
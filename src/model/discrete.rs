@@ -0,0 +1,179 @@
+//! Discrete entities and user-supplied meshes.
+//!
+//! Discrete entities don't have a CAD description, only a mesh. Use them to
+//! bring in a mesh from elsewhere, like an STL triangulation, and remesh it.
+//! ```
+//! # use rgmsh::{Gmsh, GmshResult};
+//! # use rgmsh::model::{BasicShape, CurveTag, ElementType};
+//! # fn main() -> GmshResult<()> {
+//! # let gmsh = Gmsh::initialize()?;
+//! let mut geom = gmsh.create_native_model("soup")?;
+//!
+//! // a single discrete surface holding two triangles
+//! let surf = geom.add_discrete_entity(2, &[] as &[CurveTag])?;
+//! geom.add_nodes(
+//!     surf,
+//!     &[1, 2, 3, 4],
+//!     &[0., 0., 0., 1., 0., 0., 1., 1., 0., 0., 1., 0.],
+//!     &[],
+//! )?;
+//! geom.add_elements_by_type(surf, ElementType::Triangle3, &[1, 2], &[1, 2, 3, 1, 3, 4])?;
+//!
+//! // rebuild a parametrization and remesh
+//! geom.classify_surfaces(std::f64::consts::FRAC_PI_4, true, true)?;
+//! geom.create_geometry()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use super::*;
+use crate::{check_model_error, GmshError, GmshResult};
+
+// Discrete entity methods, for models built with either kernel
+impl<'gmsh> Model<'gmsh> {
+    /// Add a discrete entity of dimension `dim`, bounded by entities of
+    /// dimension `dim - 1`.
+    ///
+    /// Fails with `ModelBadInput` for other dimensions, before adding anything.
+    pub fn add_discrete_entity<T>(&mut self, dim: i32, boundary: &[T]) -> GmshResult<BasicShape>
+    where
+        T: Into<BasicShape> + Copy,
+    {
+        if !(0..=3).contains(&dim) {
+            return Err(GmshError::ModelBadInput);
+        }
+        let boundary: Vec<(i32, i32)> = boundary.iter().map(|&b| b.into().dim_tag()).collect();
+        if boundary.iter().any(|&(d, _)| d != dim - 1) {
+            return Err(GmshError::ModelBadInput);
+        }
+        self.sync_current()?;
+        let mut raw_tags: Vec<c_int> = boundary.iter().map(|&(_, t)| t).collect();
+        let auto_number = -1;
+        unsafe {
+            let mut ierr: c_int = 0;
+            let out_tag = gmsh_sys::gmshModelAddDiscreteEntity(
+                dim,
+                auto_number,
+                raw_tags.as_mut_ptr(),
+                raw_tags.len(),
+                &mut ierr,
+            );
+            check_model_error!(ierr, ())?;
+            BasicShape::from_dim_tag(dim, out_tag)
+        }
+    }
+
+    /// Add mesh nodes to an entity. Coordinates are given as
+    /// `num_nodes × 3` values, and parametric coordinates can be empty.
+    pub fn add_nodes<T: Into<BasicShape>>(
+        &mut self,
+        entity: T,
+        node_tags: &[usize],
+        coords: &[f64],
+        parametric_coords: &[f64],
+    ) -> GmshResult<()> {
+        if coords.len() != 3 * node_tags.len() {
+            return Err(GmshError::ModelBadInput);
+        }
+        self.sync_current()?;
+        let (dim, tag) = entity.into().dim_tag();
+        // Gmsh only reads the input arrays, so there's no need to copy them
+        unsafe {
+            let mut ierr: c_int = 0;
+            gmsh_sys::gmshModelMeshAddNodes(
+                dim,
+                tag,
+                node_tags.as_ptr() as *mut usize,
+                node_tags.len(),
+                coords.as_ptr() as *mut f64,
+                coords.len(),
+                parametric_coords.as_ptr() as *mut f64,
+                parametric_coords.len(),
+                &mut ierr,
+            );
+            check_model_error!(ierr, ())
+        }
+    }
+
+    /// Add mesh elements of one type to an entity. Element nodes are
+    /// given as `num_elements × nodes per element` values.
+    pub fn add_elements_by_type<T: Into<BasicShape>>(
+        &mut self,
+        entity: T,
+        element_type: ElementType,
+        element_tags: &[usize],
+        node_tags: &[usize],
+    ) -> GmshResult<()> {
+        self.sync_current()?;
+        let (_, tag) = entity.into().dim_tag();
+        unsafe {
+            let mut ierr: c_int = 0;
+            gmsh_sys::gmshModelMeshAddElementsByType(
+                tag,
+                element_type.to_raw(),
+                element_tags.as_ptr() as *mut usize,
+                element_tags.len(),
+                node_tags.as_ptr() as *mut usize,
+                node_tags.len(),
+                &mut ierr,
+            );
+            check_model_error!(ierr, ())
+        }
+    }
+
+    /// Add mesh elements of several types to an entity, with one set of
+    /// element tags and element nodes per type.
+    pub fn add_elements<T: Into<BasicShape> + Copy>(
+        &mut self,
+        entity: T,
+        element_types: &[ElementType],
+        element_tags: &[&[usize]],
+        node_tags: &[&[usize]],
+    ) -> GmshResult<()> {
+        if element_types.len() != element_tags.len() || element_types.len() != node_tags.len() {
+            return Err(GmshError::ModelBadInput);
+        }
+        for ((&element_type, tags), nodes) in element_types
+            .iter()
+            .zip(element_tags.iter())
+            .zip(node_tags.iter())
+        {
+            self.add_elements_by_type(entity, element_type, tags, nodes)?;
+        }
+        Ok(())
+    }
+
+    /// Split discrete surfaces into patches along sharp edges, where
+    /// neighbouring triangles meet at more than `angle` radians.
+    ///
+    /// Set `boundary` to also make curves on the patch boundaries, and
+    /// `for_reparametrization` to make patches that can be reparametrized.
+    pub fn classify_surfaces(
+        &mut self,
+        angle: f64,
+        boundary: bool,
+        for_reparametrization: bool,
+    ) -> GmshResult<()> {
+        self.sync_current()?;
+        unsafe {
+            let mut ierr: c_int = 0;
+            gmsh_sys::gmshModelMeshClassifySurfaces(
+                angle,
+                boundary as c_int,
+                for_reparametrization as c_int,
+                &mut ierr,
+            );
+            check_model_error!(ierr, ())
+        }
+    }
+
+    /// Make a geometry for all discrete entities, so they can be remeshed.
+    pub fn create_geometry(&mut self) -> GmshResult<()> {
+        self.sync_current()?;
+        unsafe {
+            let mut ierr: c_int = 0;
+            gmsh_sys::gmshModelMeshCreateGeometry(&mut ierr);
+            check_model_error!(ierr, ())
+        }
+    }
+}
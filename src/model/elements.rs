@@ -0,0 +1,403 @@
+//! Mesh element types.
+//!
+//! Gmsh identifies element types with integer codes. `ElementType` names them.
+//! Element type lookups ask Gmsh, so they borrow the `Gmsh` context.
+//! ```
+//! # use rgmsh::{Gmsh, GmshResult};
+//! # use rgmsh::model::ElementType;
+//! # fn main() -> GmshResult<()> {
+//! # let gmsh = Gmsh::initialize()?;
+//! let tri6 = ElementType::from_name(&gmsh, "Triangle", 2)?;
+//! assert!(tri6 == ElementType::Triangle6);
+//!
+//! let props = tri6.properties(&gmsh)?;
+//! assert!(props.dim == 2 && props.num_nodes == 6 && props.num_primary_nodes == 3);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::interface::{string_from_raw, vec_from_raw};
+use crate::{check_model_error, get_cstring, Gmsh, GmshError, GmshResult};
+use std::os::raw::{c_char, c_int};
+
+// Declare the element type enum and its conversions from one table of Gmsh codes
+macro_rules! element_types {
+    ($($(#[$doc:meta])* $name:ident = $code:expr,)+) => {
+        /// A Gmsh mesh element type.
+        ///
+        /// The number in each name is the number of nodes of the element.
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub enum ElementType {
+            $($(#[$doc])* $name,)+
+        }
+
+        impl ElementType {
+            /// The element type code used by Gmsh.
+            pub fn to_raw(self) -> i32 {
+                match self {
+                    $(ElementType::$name => $code,)+
+                }
+            }
+
+            /// The element type for a Gmsh element type code, if it's known.
+            pub fn from_raw(code: i32) -> Option<ElementType> {
+                match code {
+                    $($code => Some(ElementType::$name),)+
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+element_types! {
+    /// 2-node line.
+    Line2 = 1,
+    /// 3-node triangle.
+    Triangle3 = 2,
+    /// 4-node quadrangle.
+    Quad4 = 3,
+    /// 4-node tetrahedron.
+    Tet4 = 4,
+    /// 8-node hexahedron.
+    Hex8 = 5,
+    /// 6-node prism.
+    Prism6 = 6,
+    /// 5-node pyramid.
+    Pyramid5 = 7,
+    /// 3-node second-order line.
+    Line3 = 8,
+    /// 6-node second-order triangle.
+    Triangle6 = 9,
+    /// 9-node second-order quadrangle.
+    Quad9 = 10,
+    /// 10-node second-order tetrahedron.
+    Tet10 = 11,
+    /// 27-node second-order hexahedron.
+    Hex27 = 12,
+    /// 18-node second-order prism.
+    Prism18 = 13,
+    /// 14-node second-order pyramid.
+    Pyramid14 = 14,
+    /// 1-node point.
+    Point1 = 15,
+    /// 8-node second-order serendipity quadrangle.
+    Quad8 = 16,
+    /// 20-node second-order serendipity hexahedron.
+    Hex20 = 17,
+    /// 15-node second-order serendipity prism.
+    Prism15 = 18,
+    /// 13-node second-order serendipity pyramid.
+    Pyramid13 = 19,
+    /// 9-node third-order incomplete triangle.
+    Triangle9 = 20,
+    /// 10-node third-order triangle.
+    Triangle10 = 21,
+    /// 12-node fourth-order incomplete triangle.
+    Triangle12 = 22,
+    /// 15-node fourth-order triangle.
+    Triangle15 = 23,
+    /// 15-node fifth-order incomplete triangle.
+    Triangle15I = 24,
+    /// 21-node fifth-order triangle.
+    Triangle21 = 25,
+    /// 4-node third-order line.
+    Line4 = 26,
+    /// 5-node fourth-order line.
+    Line5 = 27,
+    /// 6-node fifth-order line.
+    Line6 = 28,
+    /// 20-node third-order tetrahedron.
+    Tet20 = 29,
+    /// 35-node fourth-order tetrahedron.
+    Tet35 = 30,
+    /// 56-node fifth-order tetrahedron.
+    Tet56 = 31,
+    /// 22-node fourth-order incomplete tetrahedron.
+    Tet22 = 32,
+    /// 28-node fifth-order incomplete tetrahedron.
+    Tet28 = 33,
+    /// 16-node third-order quadrangle.
+    Quad16 = 36,
+    /// 25-node fourth-order quadrangle.
+    Quad25 = 37,
+    /// 36-node fifth-order quadrangle.
+    Quad36 = 38,
+    /// 12-node third-order serendipity quadrangle.
+    Quad12 = 39,
+    /// 16-node fourth-order serendipity quadrangle.
+    Quad16I = 40,
+    /// 20-node fifth-order serendipity quadrangle.
+    Quad20 = 41,
+    /// 28-node sixth-order triangle.
+    Triangle28 = 42,
+    /// 36-node seventh-order triangle.
+    Triangle36 = 43,
+    /// 45-node eighth-order triangle.
+    Triangle45 = 44,
+    /// 55-node ninth-order triangle.
+    Triangle55 = 45,
+    /// 66-node tenth-order triangle.
+    Triangle66 = 46,
+    /// 49-node sixth-order quadrangle.
+    Quad49 = 47,
+    /// 64-node seventh-order quadrangle.
+    Quad64 = 48,
+    /// 81-node eighth-order quadrangle.
+    Quad81 = 49,
+    /// 100-node ninth-order quadrangle.
+    Quad100 = 50,
+    /// 121-node tenth-order quadrangle.
+    Quad121 = 51,
+    /// 18-node sixth-order incomplete triangle.
+    Triangle18 = 52,
+    /// 21-node seventh-order incomplete triangle.
+    Triangle21I = 53,
+    /// 24-node eighth-order incomplete triangle.
+    Triangle24 = 54,
+    /// 27-node ninth-order incomplete triangle.
+    Triangle27 = 55,
+    /// 30-node tenth-order incomplete triangle.
+    Triangle30 = 56,
+    /// 24-node sixth-order serendipity quadrangle.
+    Quad24 = 57,
+    /// 28-node seventh-order serendipity quadrangle.
+    Quad28 = 58,
+    /// 32-node eighth-order serendipity quadrangle.
+    Quad32 = 59,
+    /// 36-node ninth-order serendipity quadrangle.
+    Quad36I = 60,
+    /// 40-node tenth-order serendipity quadrangle.
+    Quad40 = 61,
+    /// 7-node sixth-order line.
+    Line7 = 62,
+    /// 8-node seventh-order line.
+    Line8 = 63,
+    /// 9-node eighth-order line.
+    Line9 = 64,
+    /// 10-node ninth-order line.
+    Line10 = 65,
+    /// 11-node tenth-order line.
+    Line11 = 66,
+    /// 84-node sixth-order tetrahedron.
+    Tet84 = 71,
+    /// 120-node seventh-order tetrahedron.
+    Tet120 = 72,
+    /// 165-node eighth-order tetrahedron.
+    Tet165 = 73,
+    /// 220-node ninth-order tetrahedron.
+    Tet220 = 74,
+    /// 286-node tenth-order tetrahedron.
+    Tet286 = 75,
+    /// 34-node sixth-order incomplete tetrahedron.
+    Tet34 = 79,
+    /// 40-node seventh-order incomplete tetrahedron.
+    Tet40 = 80,
+    /// 46-node eighth-order incomplete tetrahedron.
+    Tet46 = 81,
+    /// 52-node ninth-order incomplete tetrahedron.
+    Tet52 = 82,
+    /// 58-node tenth-order incomplete tetrahedron.
+    Tet58 = 83,
+    /// 40-node third-order prism.
+    Prism40 = 90,
+    /// 75-node fourth-order prism.
+    Prism75 = 91,
+    /// 64-node third-order hexahedron.
+    Hex64 = 92,
+    /// 125-node fourth-order hexahedron.
+    Hex125 = 93,
+    /// 216-node fifth-order hexahedron.
+    Hex216 = 94,
+    /// 343-node sixth-order hexahedron.
+    Hex343 = 95,
+    /// 512-node seventh-order hexahedron.
+    Hex512 = 96,
+    /// 729-node eighth-order hexahedron.
+    Hex729 = 97,
+    /// 1000-node ninth-order hexahedron.
+    Hex1000 = 98,
+    /// 32-node third-order serendipity hexahedron.
+    Hex32 = 99,
+    /// 44-node fourth-order serendipity hexahedron.
+    Hex44 = 100,
+    /// 56-node fifth-order serendipity hexahedron.
+    Hex56 = 101,
+    /// 68-node sixth-order serendipity hexahedron.
+    Hex68 = 102,
+    /// 80-node seventh-order serendipity hexahedron.
+    Hex80 = 103,
+    /// 92-node eighth-order serendipity hexahedron.
+    Hex92 = 104,
+    /// 104-node ninth-order serendipity hexahedron.
+    Hex104 = 105,
+    /// 126-node fifth-order prism.
+    Prism126 = 106,
+    /// 196-node sixth-order prism.
+    Prism196 = 107,
+    /// 288-node seventh-order prism.
+    Prism288 = 108,
+    /// 405-node eighth-order prism.
+    Prism405 = 109,
+    /// 550-node ninth-order prism.
+    Prism550 = 110,
+    /// 24-node third-order serendipity prism.
+    Prism24 = 111,
+    /// 33-node fourth-order serendipity prism.
+    Prism33 = 112,
+    /// 42-node fifth-order serendipity prism.
+    Prism42 = 113,
+    /// 51-node sixth-order serendipity prism.
+    Prism51 = 114,
+    /// 60-node seventh-order serendipity prism.
+    Prism60 = 115,
+    /// 69-node eighth-order serendipity prism.
+    Prism69 = 116,
+    /// 78-node ninth-order serendipity prism.
+    Prism78 = 117,
+    /// 30-node third-order pyramid.
+    Pyramid30 = 118,
+    /// 55-node fourth-order pyramid.
+    Pyramid55 = 119,
+    /// 91-node fifth-order pyramid.
+    Pyramid91 = 120,
+    /// 140-node sixth-order pyramid.
+    Pyramid140 = 121,
+    /// 204-node seventh-order pyramid.
+    Pyramid204 = 122,
+    /// 285-node eighth-order pyramid.
+    Pyramid285 = 123,
+    /// 385-node ninth-order pyramid.
+    Pyramid385 = 124,
+    /// 21-node third-order serendipity pyramid.
+    Pyramid21 = 125,
+    /// 29-node fourth-order serendipity pyramid.
+    Pyramid29 = 126,
+    /// 37-node fifth-order serendipity pyramid.
+    Pyramid37 = 127,
+    /// 45-node sixth-order serendipity pyramid.
+    Pyramid45 = 128,
+    /// 53-node seventh-order serendipity pyramid.
+    Pyramid53 = 129,
+    /// 61-node eighth-order serendipity pyramid.
+    Pyramid61 = 130,
+    /// 69-node ninth-order serendipity pyramid.
+    Pyramid69 = 131,
+}
+
+/// Element type information from Gmsh.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ElementProperties {
+    /// The element name, e.g. `"Triangle 6"`.
+    pub name: String,
+    /// The element dimension.
+    pub dim: i32,
+    /// The polynomial order of the element.
+    pub order: i32,
+    /// The number of nodes.
+    pub num_nodes: usize,
+    /// The node coordinates on the reference element, `3 * num_nodes` values.
+    pub local_node_coords: Vec<f64>,
+    /// The number of primary (corner) nodes.
+    pub num_primary_nodes: usize,
+}
+
+impl ElementType {
+    /// Look up an element type from its family name (`"Point"`, `"Line"`,
+    /// `"Triangle"`, `"Quadrangle"`, `"Tetrahedron"`, `"Pyramid"`, `"Prism"`
+    /// or `"Hexahedron"`) and polynomial order.
+    pub fn from_name(_: &Gmsh, family: &str, order: i32) -> GmshResult<ElementType> {
+        let c_family = get_cstring(family)?;
+        unsafe {
+            let mut ierr: c_int = 0;
+            // complete elements only, not serendipity
+            let serendip = 0;
+            let code = gmsh_sys::gmshModelMeshGetElementType(
+                c_family.as_ptr(),
+                order,
+                serendip,
+                &mut ierr,
+            );
+            check_model_error!(ierr, ())?;
+            ElementType::from_raw(code).ok_or(GmshError::ModelBadInput)
+        }
+    }
+
+    /// The number of primary (corner) nodes of this element type.
+    pub fn num_primary_nodes(self) -> usize {
+        // variant names start with the element family
+        let families = [
+            ("Point", 1),
+            ("Line", 2),
+            ("Triangle", 3),
+            ("Quad", 4),
+            ("Tet", 4),
+            ("Hex", 8),
+            ("Prism", 6),
+            ("Pyramid", 5),
+        ];
+        let name = format!("{:?}", self);
+        families
+            .iter()
+            .find(|(family, _)| name.starts_with(family))
+            .map_or(0, |&(_, num_nodes)| num_nodes)
+    }
+
+    /// Get the properties of this element type.
+    pub fn properties(self, _: &Gmsh) -> GmshResult<ElementProperties> {
+        unsafe {
+            let mut ierr: c_int = 0;
+            let mut name: *mut c_char = std::ptr::null_mut();
+            let mut dim: c_int = 0;
+            let mut order: c_int = 0;
+            let mut num_nodes: c_int = 0;
+            let mut coords: *mut f64 = std::ptr::null_mut();
+            let mut coords_n: usize = 0;
+            gmsh_sys::gmshModelMeshGetElementProperties(
+                self.to_raw(),
+                &mut name,
+                &mut dim,
+                &mut order,
+                &mut num_nodes,
+                &mut coords,
+                &mut coords_n,
+                &mut ierr,
+            );
+            let local_node_coords = vec_from_raw(coords, coords_n);
+            let name = string_from_raw(name)?;
+            check_model_error!(
+                ierr,
+                ElementProperties {
+                    name,
+                    dim,
+                    order,
+                    num_nodes: num_nodes as usize,
+                    local_node_coords,
+                    num_primary_nodes: self.num_primary_nodes(),
+                }
+            )
+        }
+    }
+}
+
+// convert an element type code returned by Gmsh
+pub(crate) fn element_type_from_raw(code: c_int) -> GmshResult<ElementType> {
+    ElementType::from_raw(code).ok_or(GmshError::CInterface)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn primary_nodes_by_family() {
+        assert_eq!(ElementType::Point1.num_primary_nodes(), 1);
+        assert_eq!(ElementType::Line11.num_primary_nodes(), 2);
+        assert_eq!(ElementType::Triangle15I.num_primary_nodes(), 3);
+        assert_eq!(ElementType::Quad9.num_primary_nodes(), 4);
+        assert_eq!(ElementType::Tet10.num_primary_nodes(), 4);
+        assert_eq!(ElementType::Hex27.num_primary_nodes(), 8);
+        assert_eq!(ElementType::Prism18.num_primary_nodes(), 6);
+        assert_eq!(ElementType::Pyramid14.num_primary_nodes(), 5);
+    }
+}
@@ -0,0 +1,256 @@
+//! Finite element helpers.
+//!
+//! Gmsh can evaluate quadrature rules, basis functions and Jacobians on mesh
+//! elements. All results are flat `Vec<f64>` blocks in row-major order, with
+//! their shapes given alongside.
+//! ```
+//! # use rgmsh::{Gmsh, GmshResult};
+//! # use rgmsh::model::{ElementType, IntegrationRule};
+//! # fn main() -> GmshResult<()> {
+//! # let gmsh = Gmsh::initialize()?;
+//! let mut geom = gmsh.create_occ_model("model")?;
+//! geom.add_box((0., 0., 0.), (1., 1., 1.))?;
+//! geom.generate_mesh(3)?;
+//!
+//! let quadrature = ElementType::Tet4.integration_points(&gmsh, IntegrationRule::Gauss(2))?;
+//! let jacobians = geom.get_jacobians(ElementType::Tet4, IntegrationRule::Gauss(2))?;
+//!
+//! // one determinant per element and integration point
+//! assert!(jacobians.determinants.len() == jacobians.num_elements * quadrature.num_points);
+//! # Ok(())
+//! # }
+//! ```
+
+use super::*;
+use crate::interface::vec_from_raw;
+use crate::{check_model_error, get_cstring, Gmsh, GmshError, GmshResult};
+
+/// A quadrature rule for integrating over elements.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum IntegrationRule {
+    /// Gauss quadrature, exact for polynomials of the given order.
+    Gauss(u32),
+}
+
+impl IntegrationRule {
+    /// The rule name Gmsh expects, e.g. `"Gauss2"`.
+    fn to_name(self) -> String {
+        match self {
+            IntegrationRule::Gauss(order) => format!("Gauss{}", order),
+        }
+    }
+}
+
+/// A function space for evaluating basis functions.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FunctionSpace {
+    /// Lagrange basis functions of the element order.
+    Lagrange,
+    /// Gradients of the Lagrange basis functions.
+    GradLagrange,
+    /// Hierarchical H1 Legendre basis functions of the given order.
+    H1Legendre(u32),
+    /// Gradients of the hierarchical H1 Legendre basis functions.
+    GradH1Legendre(u32),
+    /// Hierarchical H(curl) Legendre basis functions of the given order.
+    HcurlLegendre(u32),
+    /// Curls of the hierarchical H(curl) Legendre basis functions.
+    CurlHcurlLegendre(u32),
+}
+
+impl FunctionSpace {
+    /// The function space name Gmsh expects, e.g. `"GradH1Legendre3"`.
+    fn to_name(self) -> String {
+        match self {
+            FunctionSpace::Lagrange => "Lagrange".to_string(),
+            FunctionSpace::GradLagrange => "GradLagrange".to_string(),
+            FunctionSpace::H1Legendre(order) => format!("H1Legendre{}", order),
+            FunctionSpace::GradH1Legendre(order) => format!("GradH1Legendre{}", order),
+            FunctionSpace::HcurlLegendre(order) => format!("HcurlLegendre{}", order),
+            FunctionSpace::CurlHcurlLegendre(order) => format!("CurlHcurlLegendre{}", order),
+        }
+    }
+}
+
+/// Integration points on the reference element.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntegrationPoints {
+    /// The number of integration points.
+    pub num_points: usize,
+    /// Local coordinates, shape `num_points × 3`.
+    pub local_coords: Vec<f64>,
+    /// Weights, shape `num_points`.
+    pub weights: Vec<f64>,
+}
+
+/// Basis functions evaluated at the integration points of the reference element.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BasisFunctions {
+    /// The number of integration points.
+    pub num_points: usize,
+    /// The number of basis functions.
+    pub num_functions: usize,
+    /// The number of components of each function, e.g. 3 for gradients.
+    pub num_components: usize,
+    /// Values, shape `num_points × num_functions × num_components`.
+    pub values: Vec<f64>,
+}
+
+/// Jacobians of the elements of one type, evaluated at integration points.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Jacobians {
+    /// The number of elements.
+    pub num_elements: usize,
+    /// The number of integration points per element.
+    pub num_points: usize,
+    /// Jacobian matrices, shape `num_elements × num_points × 9`. Each matrix
+    /// is stored by column: `dx/du, dy/du, dz/du, dx/dv, ...`.
+    pub jacobians: Vec<f64>,
+    /// Jacobian determinants, shape `num_elements × num_points`.
+    pub determinants: Vec<f64>,
+    /// Integration points in model coordinates, shape `num_elements × num_points × 3`.
+    pub points: Vec<f64>,
+}
+
+impl ElementType {
+    /// Get the integration points and weights of a quadrature rule on this element type.
+    pub fn integration_points(
+        self,
+        _: &Gmsh,
+        rule: IntegrationRule,
+    ) -> GmshResult<IntegrationPoints> {
+        self.integration_points_gen(rule)
+    }
+
+    // only call with Gmsh initialized, e.g. from a model
+    pub(crate) fn integration_points_gen(
+        self,
+        rule: IntegrationRule,
+    ) -> GmshResult<IntegrationPoints> {
+        let c_rule = get_cstring(&rule.to_name())?;
+        unsafe {
+            let mut ierr: c_int = 0;
+            let mut coords: *mut f64 = std::ptr::null_mut();
+            let mut coords_n: usize = 0;
+            let mut weights: *mut f64 = std::ptr::null_mut();
+            let mut weights_n: usize = 0;
+            gmsh_sys::gmshModelMeshGetIntegrationPoints(
+                self.to_raw(),
+                c_rule.as_ptr(),
+                &mut coords,
+                &mut coords_n,
+                &mut weights,
+                &mut weights_n,
+                &mut ierr,
+            );
+            let local_coords = vec_from_raw(coords, coords_n);
+            let weights = vec_from_raw(weights, weights_n);
+            check_model_error!(
+                ierr,
+                IntegrationPoints {
+                    num_points: weights.len(),
+                    local_coords,
+                    weights,
+                }
+            )
+        }
+    }
+
+    /// Evaluate the basis functions of a function space at the integration
+    /// points of a quadrature rule.
+    pub fn basis_functions(
+        self,
+        gmsh: &Gmsh,
+        rule: IntegrationRule,
+        space: FunctionSpace,
+    ) -> GmshResult<BasisFunctions> {
+        let mut points = self.integration_points(gmsh, rule)?;
+        let c_space = get_cstring(&space.to_name())?;
+        unsafe {
+            let mut ierr: c_int = 0;
+            let mut num_components: c_int = 0;
+            let mut values: *mut f64 = std::ptr::null_mut();
+            let mut values_n: usize = 0;
+            gmsh_sys::gmshModelMeshGetBasisFunctions(
+                self.to_raw(),
+                points.local_coords.as_mut_ptr(),
+                points.local_coords.len(),
+                c_space.as_ptr(),
+                &mut num_components,
+                &mut values,
+                &mut values_n,
+                &mut ierr,
+            );
+            let values = vec_from_raw(values, values_n);
+            check_model_error!(ierr, ())?;
+            let num_components = num_components as usize;
+            let num_points = points.num_points;
+            let num_functions = match num_points * num_components {
+                0 => 0,
+                block => values.len() / block,
+            };
+            Ok(BasisFunctions {
+                num_points,
+                num_functions,
+                num_components,
+                values,
+            })
+        }
+    }
+}
+
+// Finite element methods, for models built with either kernel
+impl<'gmsh> Model<'gmsh> {
+    /// Get the Jacobians of all mesh elements of a type at the
+    /// integration points of a quadrature rule.
+    pub fn get_jacobians(
+        &self,
+        element_type: ElementType,
+        rule: IntegrationRule,
+    ) -> GmshResult<Jacobians> {
+        self.sync_current()?;
+        let mut quadrature = element_type.integration_points_gen(rule)?;
+        let num_points = quadrature.num_points;
+        unsafe {
+            let mut ierr: c_int = 0;
+            let mut jacobians: *mut f64 = std::ptr::null_mut();
+            let mut jacobians_n: usize = 0;
+            let mut determinants: *mut f64 = std::ptr::null_mut();
+            let mut determinants_n: usize = 0;
+            let mut points: *mut f64 = std::ptr::null_mut();
+            let mut points_n: usize = 0;
+            // all entities, in a single task
+            let all_entities = -1;
+            gmsh_sys::gmshModelMeshGetJacobians(
+                element_type.to_raw(),
+                quadrature.local_coords.as_mut_ptr(),
+                quadrature.local_coords.len(),
+                &mut jacobians,
+                &mut jacobians_n,
+                &mut determinants,
+                &mut determinants_n,
+                &mut points,
+                &mut points_n,
+                all_entities,
+                0,
+                1,
+                &mut ierr,
+            );
+            let jacobians = vec_from_raw(jacobians, jacobians_n);
+            let determinants = vec_from_raw(determinants, determinants_n);
+            let points = vec_from_raw(points, points_n);
+            check_model_error!(ierr, ())?;
+            let num_elements = match num_points {
+                0 => 0,
+                n => determinants.len() / n,
+            };
+            Ok(Jacobians {
+                num_elements,
+                num_points,
+                jacobians,
+                determinants,
+                points,
+            })
+        }
+    }
+}
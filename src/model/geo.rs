@@ -1,4 +1,4 @@
-use crate::{model::*, GmshError, GmshResult};
+use crate::{model::*, trace_call, GmshError, GmshResult};
 use crate::interface::geo as factory;
 
 include!("common_geo.rs");
@@ -0,0 +1,126 @@
+//! Homology and cohomology computations.
+//!
+//! Gmsh computes (co)homology bases of physical groups on the mesh. Requests are
+//! registered first and computed when the mesh is generated, and the resulting
+//! chains are stored as new physical groups. Gmsh doesn't mark which groups are
+//! chains, so generate the mesh with `generate_mesh_with_homology` to get them.
+//! ```
+//! # use rgmsh::{Gmsh, GmshResult};
+//! # use rgmsh::model::HomologyRequest;
+//! # fn main() -> GmshResult<()> {
+//! # let gmsh = Gmsh::initialize()?;
+//! let mut geom = gmsh.create_occ_model("model")?;
+//! let conductor = geom.add_torus((0., 0., 0.), (1., 0.2))?;
+//! let domain = geom.add_physical_group(&[conductor])?;
+//!
+//! // ask for the 1D cohomology basis, i.e. the cuts of the torus
+//! geom.add_homology_request(&HomologyRequest::cohomology(&[domain]).with_dims(&[1]))?;
+//! for (name, chain) in geom.generate_mesh_with_homology(3)? {
+//!     println!("{}: {:?}", name, chain);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use super::*;
+use crate::{check_model_error, GmshError, GmshResult};
+
+/// Whether to compute homology or cohomology.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HomologyKind {
+    /// Homology spaces.
+    Homology,
+    /// Cohomology spaces.
+    Cohomology,
+}
+
+/// A request to compute the (co)homology of a domain relative to a subdomain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HomologyRequest {
+    /// Homology or cohomology.
+    pub kind: HomologyKind,
+    /// The domain physical groups. Empty for the whole mesh.
+    pub domain: Vec<PhysicalGroupTag>,
+    /// The subdomain physical groups for relative (co)homology. Empty for none.
+    pub subdomain: Vec<PhysicalGroupTag>,
+    /// The dimensions of the spaces to compute. Empty for all of them.
+    pub dims: Vec<i32>,
+}
+
+impl HomologyRequest {
+    /// Request the homology spaces of a domain.
+    pub fn homology(domain: &[PhysicalGroupTag]) -> HomologyRequest {
+        HomologyRequest {
+            kind: HomologyKind::Homology,
+            domain: domain.to_vec(),
+            subdomain: Vec::new(),
+            dims: Vec::new(),
+        }
+    }
+
+    /// Request the cohomology spaces of a domain.
+    pub fn cohomology(domain: &[PhysicalGroupTag]) -> HomologyRequest {
+        HomologyRequest {
+            kind: HomologyKind::Cohomology,
+            ..HomologyRequest::homology(domain)
+        }
+    }
+
+    /// Compute the spaces relative to a subdomain.
+    pub fn relative_to(mut self, subdomain: &[PhysicalGroupTag]) -> HomologyRequest {
+        self.subdomain = subdomain.to_vec();
+        self
+    }
+
+    /// Only compute spaces of the given dimensions.
+    pub fn with_dims(mut self, dims: &[i32]) -> HomologyRequest {
+        self.dims = dims.to_vec();
+        self
+    }
+}
+
+// Homology methods, for models built with either kernel
+impl<'gmsh> Model<'gmsh> {
+    /// Register a (co)homology request. It's computed when the mesh is generated.
+    pub fn add_homology_request(&mut self, request: &HomologyRequest) -> GmshResult<()> {
+        self.sync_current()?;
+        let mut domain: Vec<c_int> = request.domain.iter().map(|g| g.tag).collect();
+        let mut subdomain: Vec<c_int> = request.subdomain.iter().map(|g| g.tag).collect();
+        let mut dims: Vec<c_int> = request.dims.clone();
+        let compute_fn = match request.kind {
+            HomologyKind::Homology => gmsh_sys::gmshModelMeshComputeHomology,
+            HomologyKind::Cohomology => gmsh_sys::gmshModelMeshComputeCohomology,
+        };
+        unsafe {
+            let mut ierr: c_int = 0;
+            compute_fn(
+                domain.as_mut_ptr(),
+                domain.len(),
+                subdomain.as_mut_ptr(),
+                subdomain.len(),
+                dims.as_mut_ptr(),
+                dims.len(),
+                &mut ierr,
+            );
+            check_model_error!(ierr, ())
+        }
+    }
+
+    /// Mesh the model, computing the registered (co)homology requests. Returns
+    /// the chains, i.e. the physical groups added by the computation, with
+    /// their names.
+    pub fn generate_mesh_with_homology(
+        &mut self,
+        dim: i32,
+    ) -> GmshResult<Vec<(String, PhysicalGroupTag)>> {
+        let before = self.get_physical_groups()?;
+        self.generate_mesh(dim)?;
+        let mut chains = Vec::new();
+        for group in self.get_physical_groups()? {
+            if !before.contains(&group) {
+                chains.push((self.get_physical_name(group)?, group));
+            }
+        }
+        Ok(chains)
+    }
+}
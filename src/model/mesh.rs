@@ -1 +1,725 @@
 //! The Gmsh mesh module
+//!
+//! Mesh operations work on the mesh of a model after it has been generated.
+//! ```
+//! # use rgmsh::{Gmsh, GmshResult};
+//! # use rgmsh::model::OptimizeMethod;
+//! # fn main() -> GmshResult<()> {
+//! # let gmsh = Gmsh::initialize()?;
+//! let mut geom = gmsh.create_occ_model("model")?;
+//! geom.add_box((0., 0., 0.), (1., 1., 1.))?;
+//!
+//! // generate -> optimize -> elevate to second order
+//! geom.generate_mesh(3)?;
+//! geom.optimize(OptimizeMethod::Netgen, false)?;
+//! geom.set_order(2)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use super::*;
+use crate::interface::vec_from_raw;
+use crate::model::elements::element_type_from_raw;
+use crate::{check_model_error, get_cstring, GmshError, GmshResult};
+
+/// Mesh optimization methods.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum OptimizeMethod {
+    /// Gmsh's default tetrahedral mesh optimizer.
+    Default,
+    /// The Netgen tetrahedral mesh optimizer.
+    Netgen,
+    /// Untangle high-order meshes.
+    HighOrder,
+    /// Untangle high-order meshes using an elastic analogy.
+    HighOrderElastic,
+    /// Fast curving of high-order boundary layer meshes.
+    HighOrderFastCurving,
+    /// Laplacian smoothing of 2D meshes.
+    Laplace2D,
+}
+
+impl OptimizeMethod {
+    /// The method name Gmsh expects.
+    fn as_str(self) -> &'static str {
+        match self {
+            OptimizeMethod::Default => "",
+            OptimizeMethod::Netgen => "Netgen",
+            OptimizeMethod::HighOrder => "HighOrder",
+            OptimizeMethod::HighOrderElastic => "HighOrderElastic",
+            OptimizeMethod::HighOrderFastCurving => "HighOrderFastCurving",
+            OptimizeMethod::Laplace2D => "Laplace2D",
+        }
+    }
+}
+
+/// Node correspondence between a periodic entity and its master.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeriodicNodes {
+    /// The master entity.
+    pub master: BasicShape,
+    /// The nodes of the periodic entity.
+    pub node_tags: Vec<usize>,
+    /// The matching nodes on the master entity.
+    pub master_node_tags: Vec<usize>,
+    /// The affine transformation from the master, a row-major 4x4 matrix.
+    pub affine_transform: Vec<f64>,
+}
+
+/// Mesh nodes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeshNodes {
+    /// The node tags.
+    pub tags: Vec<usize>,
+    /// The node coordinates, shape `num_nodes × 3`.
+    pub coords: Vec<f64>,
+    /// The node parametric coordinates on their entities, if there are any.
+    pub parametric_coords: Vec<f64>,
+}
+
+/// Mesh elements of one type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeshElements {
+    /// The element type.
+    pub element_type: ElementType,
+    /// The element tags.
+    pub tags: Vec<usize>,
+    /// The element nodes, shape `num_elements × nodes per element`.
+    pub node_tags: Vec<usize>,
+}
+
+/// A mesh element found from model coordinates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ElementLocation {
+    /// The element tag.
+    pub tag: usize,
+    /// The element type.
+    pub element_type: ElementType,
+    /// The element nodes.
+    pub node_tags: Vec<usize>,
+    /// The point in the element's local coordinates `(u, v, w)`.
+    pub local_coords: (f64, f64, f64),
+}
+
+/// Element face shapes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FaceType {
+    /// Triangular faces.
+    Triangle = 3,
+    /// Quadrangular faces.
+    Quad = 4,
+}
+
+// Mesh methods, for models built with either kernel
+impl<'gmsh> Model<'gmsh> {
+    /// Refine the mesh by uniformly splitting its elements.
+    pub fn refine(&mut self) -> GmshResult<()> {
+        self.sync_current()?;
+        unsafe {
+            let mut ierr: c_int = 0;
+            gmsh_sys::gmshModelMeshRefine(&mut ierr);
+            check_model_error!(ierr, ())
+        }
+    }
+
+    /// Optimize the mesh with the given method.
+    /// Set `force` to also optimize meshes of discrete entities.
+    pub fn optimize(&mut self, method: OptimizeMethod, force: bool) -> GmshResult<()> {
+        self.sync_current()?;
+        let c_method = get_cstring(method.as_str())?;
+        unsafe {
+            let mut ierr: c_int = 0;
+            gmsh_sys::gmshModelMeshOptimize(c_method.as_ptr(), force as c_int, &mut ierr);
+            check_model_error!(ierr, ())
+        }
+    }
+
+    /// Set the order of the mesh elements, e.g. `2` for second-order elements.
+    pub fn set_order(&mut self, order: i32) -> GmshResult<()> {
+        self.sync_current()?;
+        unsafe {
+            let mut ierr: c_int = 0;
+            gmsh_sys::gmshModelMeshSetOrder(order, &mut ierr);
+            check_model_error!(ierr, ())
+        }
+    }
+
+    /// Recombine the triangles of the mesh into quadrangles.
+    pub fn recombine(&mut self) -> GmshResult<()> {
+        self.sync_current()?;
+        unsafe {
+            let mut ierr: c_int = 0;
+            gmsh_sys::gmshModelMeshRecombine(&mut ierr);
+            check_model_error!(ierr, ())
+        }
+    }
+
+    /// Partition the mesh into `num_parts` parts.
+    pub fn partition(&mut self, num_parts: i32) -> GmshResult<()> {
+        self.sync_current()?;
+        unsafe {
+            let mut ierr: c_int = 0;
+            gmsh_sys::gmshModelMeshPartition(num_parts, &mut ierr);
+            check_model_error!(ierr, ())
+        }
+    }
+
+    /// Undo a mesh partition.
+    pub fn unpartition(&mut self) -> GmshResult<()> {
+        self.sync_current()?;
+        unsafe {
+            let mut ierr: c_int = 0;
+            gmsh_sys::gmshModelMeshUnpartition(&mut ierr);
+            check_model_error!(ierr, ())
+        }
+    }
+
+    /// Delete the mesh, keeping the geometry.
+    pub fn clear_mesh(&mut self) -> GmshResult<()> {
+        self.sync_current()?;
+        unsafe {
+            let mut ierr: c_int = 0;
+            gmsh_sys::gmshModelMeshClear(&mut ierr);
+            check_model_error!(ierr, ())
+        }
+    }
+
+    /// Merge mesh nodes that share the same position.
+    pub fn remove_duplicate_nodes(&mut self) -> GmshResult<()> {
+        self.sync_current()?;
+        unsafe {
+            let mut ierr: c_int = 0;
+            gmsh_sys::gmshModelMeshRemoveDuplicateNodes(&mut ierr);
+            check_model_error!(ierr, ())
+        }
+    }
+
+    /// Embed lower-dimensional entities in the mesh of `in_entity`, e.g.
+    /// force the mesh of a surface to conform to points and curves inside it.
+    ///
+    /// `in_entity` must be a surface or a volume.
+    pub fn embed<T, U>(&mut self, entities: &[T], in_entity: U) -> GmshResult<()>
+    where
+        T: Into<BasicShape> + Copy,
+        U: Into<BasicShape>,
+    {
+        let (in_dim, in_tag) = in_entity.into().dim_tag();
+        // entities of the same or a higher dimension can't be embedded
+        if entities.iter().any(|&e| e.into().dim_tag().0 >= in_dim) {
+            return Err(GmshError::ModelBadInput);
+        }
+        self.sync_current()?;
+        // Gmsh embeds one dimension at a time
+        for dim in 0..in_dim {
+            let mut raw_tags: Vec<c_int> = entities
+                .iter()
+                .map(|&e| e.into().dim_tag())
+                .filter(|&(d, _)| d == dim)
+                .map(|(_, t)| t)
+                .collect();
+            if raw_tags.is_empty() {
+                continue;
+            }
+            unsafe {
+                let mut ierr: c_int = 0;
+                gmsh_sys::gmshModelMeshEmbed(
+                    dim,
+                    raw_tags.as_mut_ptr(),
+                    raw_tags.len(),
+                    in_dim,
+                    in_tag,
+                    &mut ierr,
+                );
+                check_model_error!(ierr, ())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Make the mesh of `entities` a copy of the mesh of `masters`, mapped
+    /// with a 4x4 affine transformation matrix given in row-major order.
+    ///
+    /// All entities must have the same dimension.
+    pub fn set_periodic<T>(
+        &mut self,
+        entities: &[T],
+        masters: &[T],
+        affine_transform: &[f64; 16],
+    ) -> GmshResult<()>
+    where
+        T: Into<BasicShape> + Copy,
+    {
+        self.sync_current()?;
+        let dim_tags: Vec<(i32, i32)> = entities.iter().map(|&e| e.into().dim_tag()).collect();
+        let master_dim_tags: Vec<(i32, i32)> =
+            masters.iter().map(|&e| e.into().dim_tag()).collect();
+        let dim = match dim_tags.first() {
+            Some(&(dim, _)) => dim,
+            None => return Ok(()),
+        };
+        if dim_tags
+            .iter()
+            .chain(master_dim_tags.iter())
+            .any(|&(d, _)| d != dim)
+        {
+            return Err(GmshError::ModelBadInput);
+        }
+        let mut raw_tags: Vec<c_int> = dim_tags.iter().map(|&(_, t)| t).collect();
+        let mut raw_masters: Vec<c_int> = master_dim_tags.iter().map(|&(_, t)| t).collect();
+        let mut transform = affine_transform.to_vec();
+        unsafe {
+            let mut ierr: c_int = 0;
+            gmsh_sys::gmshModelMeshSetPeriodic(
+                dim,
+                raw_tags.as_mut_ptr(),
+                raw_tags.len(),
+                raw_masters.as_mut_ptr(),
+                raw_masters.len(),
+                transform.as_mut_ptr(),
+                transform.len(),
+                &mut ierr,
+            );
+            check_model_error!(ierr, ())
+        }
+    }
+
+    /// Get the periodic node pairs of a periodic entity.
+    pub fn get_periodic_nodes<T: Into<BasicShape>>(&self, entity: T) -> GmshResult<PeriodicNodes> {
+        self.sync_current()?;
+        let (dim, tag) = entity.into().dim_tag();
+        unsafe {
+            let mut ierr: c_int = 0;
+            let mut master_tag: c_int = 0;
+            let mut node_tags: *mut usize = std::ptr::null_mut();
+            let mut node_tags_n: usize = 0;
+            let mut master_node_tags: *mut usize = std::ptr::null_mut();
+            let mut master_node_tags_n: usize = 0;
+            let mut transform: *mut f64 = std::ptr::null_mut();
+            let mut transform_n: usize = 0;
+            gmsh_sys::gmshModelMeshGetPeriodicNodes(
+                dim,
+                tag,
+                &mut master_tag,
+                &mut node_tags,
+                &mut node_tags_n,
+                &mut master_node_tags,
+                &mut master_node_tags_n,
+                &mut transform,
+                &mut transform_n,
+                &mut ierr,
+            );
+            check_model_error!(ierr, ())?;
+            Ok(PeriodicNodes {
+                master: BasicShape::from_dim_tag(dim, master_tag)?,
+                node_tags: vec_from_raw(node_tags, node_tags_n),
+                master_node_tags: vec_from_raw(master_node_tags, master_node_tags_n),
+                affine_transform: vec_from_raw(transform, transform_n),
+            })
+        }
+    }
+
+    /// Get the mesh nodes classified on an entity, optionally including
+    /// the nodes on its boundary.
+    /// ```
+    /// # use rgmsh::{Gmsh, GmshResult};
+    /// # fn main() -> GmshResult<()> {
+    /// # let gmsh = Gmsh::initialize()?;
+    /// let mut geom = gmsh.create_native_model("model")?;
+    /// let p1 = geom.add_point(0., 0., 0.)?;
+    /// let p2 = geom.add_point(1., 0., 0.)?;
+    /// let line = geom.add_line(p1, p2)?;
+    /// geom.generate_mesh(1)?;
+    ///
+    /// // the interior nodes, and then the end points as well
+    /// let interior = geom.get_nodes(line, false)?;
+    /// let all = geom.get_nodes(line, true)?;
+    /// assert!(all.tags.len() == interior.tags.len() + 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_nodes<T: Into<BasicShape>>(
+        &self,
+        entity: T,
+        include_boundary: bool,
+    ) -> GmshResult<MeshNodes> {
+        let (dim, tag) = entity.into().dim_tag();
+        self.get_nodes_gen(dim, tag, include_boundary)
+    }
+
+    // shared with the remote server, which asks for all nodes with `(-1, -1)`
+    pub(crate) fn get_nodes_gen(
+        &self,
+        dim: c_int,
+        tag: c_int,
+        include_boundary: bool,
+    ) -> GmshResult<MeshNodes> {
+        self.sync_current()?;
+        unsafe {
+            let mut ierr: c_int = 0;
+            let mut node_tags: *mut usize = std::ptr::null_mut();
+            let mut node_tags_n: usize = 0;
+            let mut coords: *mut f64 = std::ptr::null_mut();
+            let mut coords_n: usize = 0;
+            let mut param_coords: *mut f64 = std::ptr::null_mut();
+            let mut param_coords_n: usize = 0;
+            let return_param_coords = 1;
+            gmsh_sys::gmshModelMeshGetNodes(
+                &mut node_tags,
+                &mut node_tags_n,
+                &mut coords,
+                &mut coords_n,
+                &mut param_coords,
+                &mut param_coords_n,
+                dim,
+                tag,
+                include_boundary as c_int,
+                return_param_coords,
+                &mut ierr,
+            );
+            let nodes = MeshNodes {
+                tags: vec_from_raw(node_tags, node_tags_n),
+                coords: vec_from_raw(coords, coords_n),
+                parametric_coords: vec_from_raw(param_coords, param_coords_n),
+            };
+            check_model_error!(ierr, nodes)
+        }
+    }
+
+    /// Get the coordinates and parametric coordinates of a single mesh node.
+    pub fn get_node(&self, node_tag: usize) -> GmshResult<((f64, f64, f64), Vec<f64>)> {
+        self.sync_current()?;
+        unsafe {
+            let mut ierr: c_int = 0;
+            let mut coords: *mut f64 = std::ptr::null_mut();
+            let mut coords_n: usize = 0;
+            let mut param_coords: *mut f64 = std::ptr::null_mut();
+            let mut param_coords_n: usize = 0;
+            gmsh_sys::gmshModelMeshGetNode(
+                node_tag,
+                &mut coords,
+                &mut coords_n,
+                &mut param_coords,
+                &mut param_coords_n,
+                &mut ierr,
+            );
+            let coords = vec_from_raw(coords, coords_n);
+            let param_coords = vec_from_raw(param_coords, param_coords_n);
+            check_model_error!(ierr, ())?;
+            match coords[..] {
+                [x, y, z] => Ok(((x, y, z), param_coords)),
+                _ => Err(GmshError::CInterface),
+            }
+        }
+    }
+
+    /// Get the nodes of all elements of a type on an entity.
+    pub fn get_nodes_by_element_type<T: Into<BasicShape>>(
+        &self,
+        element_type: ElementType,
+        entity: T,
+    ) -> GmshResult<MeshNodes> {
+        self.sync_current()?;
+        let (_, tag) = entity.into().dim_tag();
+        unsafe {
+            let mut ierr: c_int = 0;
+            let mut node_tags: *mut usize = std::ptr::null_mut();
+            let mut node_tags_n: usize = 0;
+            let mut coords: *mut f64 = std::ptr::null_mut();
+            let mut coords_n: usize = 0;
+            let mut param_coords: *mut f64 = std::ptr::null_mut();
+            let mut param_coords_n: usize = 0;
+            let return_param_coords = 1;
+            gmsh_sys::gmshModelMeshGetNodesByElementType(
+                element_type.to_raw(),
+                &mut node_tags,
+                &mut node_tags_n,
+                &mut coords,
+                &mut coords_n,
+                &mut param_coords,
+                &mut param_coords_n,
+                tag,
+                return_param_coords,
+                &mut ierr,
+            );
+            let nodes = MeshNodes {
+                tags: vec_from_raw(node_tags, node_tags_n),
+                coords: vec_from_raw(coords, coords_n),
+                parametric_coords: vec_from_raw(param_coords, param_coords_n),
+            };
+            check_model_error!(ierr, nodes)
+        }
+    }
+
+    /// Get the types of the mesh elements on an entity.
+    pub fn get_element_types<T: Into<BasicShape>>(
+        &self,
+        entity: T,
+    ) -> GmshResult<Vec<ElementType>> {
+        let (dim, tag) = entity.into().dim_tag();
+        self.get_element_types_gen(dim, tag)
+    }
+
+    // shared with the other mesh query modules
+    pub(crate) fn get_element_types_gen(
+        &self,
+        dim: c_int,
+        tag: c_int,
+    ) -> GmshResult<Vec<ElementType>> {
+        self.sync_current()?;
+        unsafe {
+            let mut ierr: c_int = 0;
+            let mut element_types: *mut c_int = std::ptr::null_mut();
+            let mut element_types_n: usize = 0;
+            gmsh_sys::gmshModelMeshGetElementTypes(
+                &mut element_types,
+                &mut element_types_n,
+                dim,
+                tag,
+                &mut ierr,
+            );
+            let element_types = vec_from_raw(element_types, element_types_n);
+            check_model_error!(ierr, ())?;
+            element_types
+                .into_iter()
+                .map(element_type_from_raw)
+                .collect()
+        }
+    }
+
+    /// Get all elements of a type on an entity.
+    pub fn get_elements_by_type<T: Into<BasicShape>>(
+        &self,
+        element_type: ElementType,
+        entity: T,
+    ) -> GmshResult<MeshElements> {
+        let (_, tag) = entity.into().dim_tag();
+        self.get_elements_by_type_gen(element_type, tag)
+    }
+
+    // shared with the other mesh query modules
+    pub(crate) fn get_elements_by_type_gen(
+        &self,
+        element_type: ElementType,
+        tag: c_int,
+    ) -> GmshResult<MeshElements> {
+        self.sync_current()?;
+        unsafe {
+            let mut ierr: c_int = 0;
+            let mut element_tags: *mut usize = std::ptr::null_mut();
+            let mut element_tags_n: usize = 0;
+            let mut node_tags: *mut usize = std::ptr::null_mut();
+            let mut node_tags_n: usize = 0;
+            gmsh_sys::gmshModelMeshGetElementsByType(
+                element_type.to_raw(),
+                &mut element_tags,
+                &mut element_tags_n,
+                &mut node_tags,
+                &mut node_tags_n,
+                tag,
+                0,
+                1,
+                &mut ierr,
+            );
+            let elements = MeshElements {
+                element_type,
+                tags: vec_from_raw(element_tags, element_tags_n),
+                node_tags: vec_from_raw(node_tags, node_tags_n),
+            };
+            check_model_error!(ierr, elements)
+        }
+    }
+
+    /// Get the type and nodes of a single mesh element.
+    pub fn get_element(&self, element_tag: usize) -> GmshResult<(ElementType, Vec<usize>)> {
+        self.sync_current()?;
+        unsafe {
+            let mut ierr: c_int = 0;
+            let mut element_type: c_int = 0;
+            let mut node_tags: *mut usize = std::ptr::null_mut();
+            let mut node_tags_n: usize = 0;
+            gmsh_sys::gmshModelMeshGetElement(
+                element_tag,
+                &mut element_type,
+                &mut node_tags,
+                &mut node_tags_n,
+                &mut ierr,
+            );
+            let node_tags = vec_from_raw(node_tags, node_tags_n);
+            check_model_error!(ierr, ())?;
+            Ok((element_type_from_raw(element_type)?, node_tags))
+        }
+    }
+
+    /// Find the element of dimension `dim` containing the point `(x, y, z)`.
+    /// Set `strict` to only accept points strictly inside the element.
+    pub fn get_element_by_coordinates(
+        &self,
+        coords: (f64, f64, f64),
+        dim: i32,
+        strict: bool,
+    ) -> GmshResult<ElementLocation> {
+        self.sync_current()?;
+        unsafe {
+            let mut ierr: c_int = 0;
+            let mut element_tag: usize = 0;
+            let mut element_type: c_int = 0;
+            let mut node_tags: *mut usize = std::ptr::null_mut();
+            let mut node_tags_n: usize = 0;
+            let (mut u, mut v, mut w) = (0., 0., 0.);
+            gmsh_sys::gmshModelMeshGetElementByCoordinates(
+                coords.0,
+                coords.1,
+                coords.2,
+                &mut element_tag,
+                &mut element_type,
+                &mut node_tags,
+                &mut node_tags_n,
+                &mut u,
+                &mut v,
+                &mut w,
+                dim,
+                strict as c_int,
+                &mut ierr,
+            );
+            let node_tags = vec_from_raw(node_tags, node_tags_n);
+            check_model_error!(ierr, ())?;
+            Ok(ElementLocation {
+                tag: element_tag,
+                element_type: element_type_from_raw(element_type)?,
+                node_tags,
+                local_coords: (u, v, w),
+            })
+        }
+    }
+
+    /// Get the nodes of the edges of all elements of a type on an entity,
+    /// two nodes per edge. Set `primary` to only return corner nodes.
+    pub fn get_element_edge_nodes<T: Into<BasicShape>>(
+        &self,
+        element_type: ElementType,
+        entity: T,
+        primary: bool,
+    ) -> GmshResult<Vec<usize>> {
+        self.sync_current()?;
+        let (_, tag) = entity.into().dim_tag();
+        unsafe {
+            let mut ierr: c_int = 0;
+            let mut node_tags: *mut usize = std::ptr::null_mut();
+            let mut node_tags_n: usize = 0;
+            gmsh_sys::gmshModelMeshGetElementEdgeNodes(
+                element_type.to_raw(),
+                &mut node_tags,
+                &mut node_tags_n,
+                tag,
+                primary as c_int,
+                0,
+                1,
+                &mut ierr,
+            );
+            let node_tags = vec_from_raw(node_tags, node_tags_n);
+            check_model_error!(ierr, node_tags)
+        }
+    }
+
+    /// Get the nodes of the triangular or quadrangular faces of all
+    /// elements of a type on an entity. Set `primary` to only return corner nodes.
+    pub fn get_element_face_nodes<T: Into<BasicShape>>(
+        &self,
+        element_type: ElementType,
+        face_type: FaceType,
+        entity: T,
+        primary: bool,
+    ) -> GmshResult<Vec<usize>> {
+        self.sync_current()?;
+        let (_, tag) = entity.into().dim_tag();
+        unsafe {
+            let mut ierr: c_int = 0;
+            let mut node_tags: *mut usize = std::ptr::null_mut();
+            let mut node_tags_n: usize = 0;
+            gmsh_sys::gmshModelMeshGetElementFaceNodes(
+                element_type.to_raw(),
+                face_type as c_int,
+                &mut node_tags,
+                &mut node_tags_n,
+                tag,
+                primary as c_int,
+                0,
+                1,
+                &mut ierr,
+            );
+            let node_tags = vec_from_raw(node_tags, node_tags_n);
+            check_model_error!(ierr, node_tags)
+        }
+    }
+
+    /// Get the barycenters of all elements of a type on an entity,
+    /// shape `num_elements × 3`. Set `primary` to only use corner nodes.
+    pub fn get_barycenters<T: Into<BasicShape>>(
+        &self,
+        element_type: ElementType,
+        entity: T,
+        primary: bool,
+    ) -> GmshResult<Vec<f64>> {
+        let (_, tag) = entity.into().dim_tag();
+        self.get_barycenters_gen(element_type, tag, primary)
+    }
+
+    // shared with the other mesh query modules
+    pub(crate) fn get_barycenters_gen(
+        &self,
+        element_type: ElementType,
+        tag: c_int,
+        primary: bool,
+    ) -> GmshResult<Vec<f64>> {
+        self.sync_current()?;
+        unsafe {
+            let mut ierr: c_int = 0;
+            let mut barycenters: *mut f64 = std::ptr::null_mut();
+            let mut barycenters_n: usize = 0;
+            // don't take the fast path, which returns unnormalized sums
+            let fast = 0;
+            gmsh_sys::gmshModelMeshGetBarycenters(
+                element_type.to_raw(),
+                tag,
+                fast,
+                primary as c_int,
+                &mut barycenters,
+                &mut barycenters_n,
+                0,
+                1,
+                &mut ierr,
+            );
+            let barycenters = vec_from_raw(barycenters, barycenters_n);
+            check_model_error!(ierr, barycenters)
+        }
+    }
+
+    /// Get the ghost elements of an entity in a partitioned mesh, with
+    /// the partition each one belongs to.
+    pub fn get_ghost_elements<T: Into<BasicShape>>(
+        &self,
+        entity: T,
+    ) -> GmshResult<(Vec<usize>, Vec<i32>)> {
+        self.sync_current()?;
+        let (dim, tag) = entity.into().dim_tag();
+        unsafe {
+            let mut ierr: c_int = 0;
+            let mut element_tags: *mut usize = std::ptr::null_mut();
+            let mut element_tags_n: usize = 0;
+            let mut partitions: *mut c_int = std::ptr::null_mut();
+            let mut partitions_n: usize = 0;
+            gmsh_sys::gmshModelMeshGetGhostElements(
+                dim,
+                tag,
+                &mut element_tags,
+                &mut element_tags_n,
+                &mut partitions,
+                &mut partitions_n,
+                &mut ierr,
+            );
+            let element_tags = vec_from_raw(element_tags, element_tags_n);
+            let partitions = vec_from_raw(partitions, partitions_n);
+            check_model_error!(ierr, (element_tags, partitions))
+        }
+    }
+}
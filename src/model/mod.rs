@@ -6,6 +6,11 @@
 //!
 //! Either should suffice for most projects.
 //!
+//! Both build a [`Model`](crate::model::Model), which holds the entities,
+//! physical groups and mesh regardless of kernel. Kernel models dereference to
+//! their `Model`, and [`into_model`](crate::model::GeoModel::into_model) hands
+//! it over once the geometry is done.
+//!
 //! ## Creating a model
 //!
 //! The only way to get a model is through a [`Gmsh`](crate::Gmsh) context object.
@@ -230,13 +235,13 @@
 //!   using tags from one model in another.
 //!
 
-use crate::{check_main_error, check_model_error, get_cstring, Gmsh, GmshError, GmshResult};
+use crate::{check_main_error, check_model_error, get_cstring, trace_call, Gmsh, GmshError, GmshResult};
 
 use std::ffi::{CStr, CString};
 use std::os::raw::c_int;
 
-use std::marker::PhantomData;
-use std::ops::Neg;
+use crate::interface::vec_from_raw;
+use std::ops::{Deref, DerefMut, Neg};
 
 // gmsh_sys interface
 pub use crate::interface::{geo::*, occ::*};
@@ -244,6 +249,30 @@ pub use crate::interface::{geo::*, occ::*};
 pub mod shapes;
 pub use shapes::*;
 
+pub mod mesh;
+pub use mesh::*;
+
+pub mod elements;
+pub use elements::*;
+
+pub mod fem;
+pub use fem::*;
+
+pub mod discrete;
+
+pub mod quality;
+pub use quality::*;
+
+pub mod reorder;
+
+pub mod physical;
+
+pub mod homology;
+pub use homology::*;
+
+pub mod quad;
+pub use quad::*;
+
 /// Add points to a geometry model inline.
 ///
 /// You can use `add_points!` to create a series of points inline.
@@ -298,103 +327,285 @@ macro_rules! add_points {
     }
 }
 
-/// An instance of the built-in geometry kernel.
-pub struct GeoModel<'gmsh> {
+/// A Gmsh model: its entities, physical groups and mesh, whichever geometry
+/// kernel built it.
+///
+/// Geometry kernels keep their own copy of the shapes they make. The model is
+/// synchronized with the kernel when needed, before queries and meshing. The
+/// `Gmsh` context tracks changes per model name, so every handle to a model
+/// sees them.
+/// ```
+/// # use rgmsh::{Gmsh, GmshResult};
+/// # use rgmsh::model::Model;
+/// # fn main() -> GmshResult<()> {
+/// # let gmsh = Gmsh::initialize()?;
+/// let mut geom = gmsh.create_occ_model("model")?;
+/// geom.add_box((0., 0., 0.), (1., 1., 1.))?;
+///
+/// let mut model: Model = geom.into_model()?;
+/// assert_eq!(model.get_entities(Some(3))?.len(), 1);
+/// model.generate_mesh(3)?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct Model<'gmsh> {
     /// The model name.
-    pub name: &'static str,
+    pub name: String,
     /// The model name used to talk to C.
     pub c_name: CString,
-    phantom: PhantomData<&'gmsh Gmsh>,
+    // the context tracks which models need synchronizing, for all handles
+    gmsh: &'gmsh Gmsh,
+}
+
+// The geometry kernels that can build a model
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Kernel {
+    BuiltIn,
+    OpenCascade,
+}
+
+impl Kernel {
+    fn synchronize(self) -> GmshResult<()> {
+        let sync_fn = match self {
+            Kernel::BuiltIn => crate::interface::geo::synchronize,
+            Kernel::OpenCascade => crate::interface::occ::synchronize,
+        };
+        unsafe {
+            let mut ierr: c_int = 0;
+            sync_fn(&mut ierr);
+            check_model_error!(ierr, ())
+        }
+    }
+}
+
+impl<'gmsh> Model<'gmsh> {
+    fn new(gmsh: &'gmsh Gmsh, name: String) -> GmshResult<Self> {
+        let c_name = get_cstring(&name)?;
+        Ok(Model { name, c_name, gmsh })
+    }
+
+    /// Get a handle to an existing model, e.g. one read from a file.
+    ///
+    /// Fails with `ModelLookup` if there's no model with that name.
+    pub fn by_name<N: Into<String>>(gmsh: &'gmsh Gmsh, name: N) -> GmshResult<Self> {
+        let name = name.into();
+        if !gmsh.list_models()?.contains(&name) {
+            return Err(GmshError::ModelLookup);
+        }
+        Model::new(gmsh, name)
+    }
+
+    /// Remove model from Gmsh.
+    pub fn remove(self) -> GmshResult<()> {
+        // first set this model to the current model.
+        self.set_current()?;
+        // now, remove the current model
+        unsafe {
+            let mut ierr: c_int = 0;
+            gmsh_sys::gmshModelRemove(&mut ierr);
+            check_main_error!(ierr, ())?;
+        }
+        let mut unsynchronized = self.gmsh.unsynchronized.borrow_mut();
+        unsynchronized.retain(|(name, _)| *name != self.name);
+        // Gmsh picks another current model
+        *self.gmsh.current.borrow_mut() = None;
+        Ok(())
+    }
+
+    /// Set model to current model.
+    pub fn set_current(&self) -> GmshResult<()> {
+        unsafe {
+            let mut ierr: c_int = 0;
+            gmsh_sys::gmshModelSetCurrent(self.c_name.as_ptr(), &mut ierr);
+            if ierr != 0 {
+                return Err(GmshError::Execution);
+            }
+        }
+        *self.gmsh.current.borrow_mut() = Some(self.name.clone());
+        Ok(())
+    }
+
+    /// Check if the model has seen every change made through its geometry kernel.
+    pub fn is_synchronized(&self) -> bool {
+        let unsynchronized = self.gmsh.unsynchronized.borrow();
+        !unsynchronized.iter().any(|(name, _)| *name == self.name)
+    }
+
+    // Remember a kernel change, until the model is synchronized with that kernel
+    fn mark_unsynchronized(&self, kernel: Kernel) {
+        let mut unsynchronized = self.gmsh.unsynchronized.borrow_mut();
+        if !unsynchronized.iter().any(|(name, k)| *name == self.name && *k == kernel) {
+            unsynchronized.push((self.name.clone(), kernel));
+        }
+    }
+
+    // Synchronize the changes of one kernel into the current model
+    fn synchronize_kernel(&self, kernel: Kernel) -> GmshResult<()> {
+        let result = kernel.synchronize();
+        trace_call!(self.name, "synchronize()" => result);
+        result?;
+        let mut unsynchronized = self.gmsh.unsynchronized.borrow_mut();
+        unsynchronized.retain(|(name, k)| !(*name == self.name && *k == kernel));
+        Ok(())
+    }
+
+    // Set this model as current and bring in any pending geometry changes
+    pub(crate) fn sync_current(&self) -> GmshResult<()> {
+        self.set_current()?;
+        let pending: Vec<Kernel> = self
+            .gmsh
+            .unsynchronized
+            .borrow()
+            .iter()
+            .filter(|(name, _)| *name == self.name)
+            .map(|&(_, kernel)| kernel)
+            .collect();
+        for kernel in pending {
+            self.synchronize_kernel(kernel)?;
+        }
+        Ok(())
+    }
+
+    /// Get the entities of a dimension, or of all dimensions for `None`.
+    pub fn get_entities(&self, dim: Option<i32>) -> GmshResult<Vec<BasicShape>> {
+        self.sync_current()?;
+        unsafe {
+            let mut ierr: c_int = 0;
+            let mut dim_tags: *mut c_int = std::ptr::null_mut();
+            let mut dim_tags_n: usize = 0;
+            gmsh_sys::gmshModelGetEntities(
+                &mut dim_tags,
+                &mut dim_tags_n,
+                dim.unwrap_or(-1),
+                &mut ierr,
+            );
+            let dim_tags = vec_from_raw(dim_tags, dim_tags_n);
+            check_model_error!(ierr, ())?;
+            dim_tags
+                .chunks_exact(2)
+                .map(|dt| BasicShape::from_dim_tag(dt[0], dt[1]))
+                .collect()
+        }
+    }
+
+    /// Get the bounding box of an entity, as its `(min, max)` corners.
+    pub fn get_bounding_box<T: Into<BasicShape>>(&self, entity: T) -> GmshResult<(Point, Point)> {
+        self.sync_current()?;
+        let (dim, tag) = entity.into().dim_tag();
+        let mut min = Point { x: 0., y: 0., z: 0. };
+        let mut max = Point { x: 0., y: 0., z: 0. };
+        unsafe {
+            let mut ierr: c_int = 0;
+            gmsh_sys::gmshModelGetBoundingBox(
+                dim, tag, &mut min.x, &mut min.y, &mut min.z, &mut max.x, &mut max.y,
+                &mut max.z, &mut ierr,
+            );
+            check_model_error!(ierr, (min, max))
+        }
+    }
+
+    /// Mesh the model.
+    pub fn generate_mesh(&mut self, dim: i32) -> GmshResult<()> {
+        // only synchronizes if the geometry changed
+        self.sync_current()?;
+        unsafe {
+            let mut ierr: c_int = 0;
+            gmsh_sys::gmshModelMeshGenerate(dim, &mut ierr);
+            // pass on meshing messages, even if meshing failed
+            crate::logger::forward()?;
+            let result = check_model_error!(ierr, ());
+            trace_call!(self.name, "generate_mesh({})", dim => result);
+            result
+        }
+    }
+}
+
+/// A model built with the built-in geometry kernel.
+///
+/// Everything a [`Model`] can do is available through `Deref`.
+pub struct GeoModel<'gmsh> {
+    model: Model<'gmsh>,
 }
 
-/// An instance of the `OpenCASCADE` geometry kernel.
+/// A model built with the `OpenCASCADE` geometry kernel.
+///
+/// Everything a [`Model`] can do is available through `Deref`.
 pub struct OccModel<'gmsh> {
-    /// The model name.
-    pub name: &'static str,
-    /// The model name used to talk to C.
-    pub c_name: CString,
-    phantom: PhantomData<&'gmsh Gmsh>,
+    model: Model<'gmsh>,
 }
 
-// General model methods
+// Kernel builder methods
 macro_rules! impl_model {
 
-    (@kernel_prefix GeoModel, $fn_name: ident) => {
-        crate::interface::geo::$fn_name
+    (@kernel GeoModel) => {
+        Kernel::BuiltIn
     };
 
-    (@kernel_prefix OccModel, $fn_name: ident) => {
-         crate::interface::occ::$fn_name
+    (@kernel OccModel) => {
+        Kernel::OpenCascade
     };
 
     ($model_type: ident) => {
         impl<'gmsh> $model_type<'gmsh> {
             /// Create a new Gmsh model.
-            // todo: fix me for setting which model is the current one.
-            // idea: keep a list of already used model names and only allow one at once
-            #[must_use]
-            pub fn create(_: &'gmsh Gmsh, name: &'static str) -> GmshResult<Self> {
-                let c_name = get_cstring(name)?;
+            pub fn create<N: Into<String>>(gmsh: &'gmsh Gmsh, name: N) -> GmshResult<Self> {
+                let model = Model::new(gmsh, name.into())?;
                 unsafe {
                     let mut ierr: c_int = 0;
                     // also sets the added model as the current model
-                    gmsh_sys::gmshModelAdd(c_name.as_ptr(), &mut ierr);
-                    let model = $model_type {
-                        name,
-                        c_name,
-                        phantom: PhantomData,
-                    };
-                    check_main_error!(ierr, model)
+                    gmsh_sys::gmshModelAdd(model.c_name.as_ptr(), &mut ierr);
+                    trace_call!(model.name, "create({:?})", model.name => ierr);
+                    check_main_error!(ierr, ())?;
                 }
+                *gmsh.current.borrow_mut() = Some(model.name.clone());
+                Ok($model_type { model })
+            }
+
+            /// Get a handle to an existing model, e.g. one read from a file.
+            ///
+            /// Fails with `ModelLookup` if there's no model with that name.
+            pub fn by_name<N: Into<String>>(gmsh: &'gmsh Gmsh, name: N) -> GmshResult<Self> {
+                Ok($model_type { model: Model::by_name(gmsh, name)? })
             }
 
             /// Remove model from Gmsh.
             pub fn remove(self) -> GmshResult<()> {
-                 // first set this model to the current model.
-                 self.set_current()?;
-                 // now, remove the current model
-                 unsafe {
-                     let mut ierr: c_int = 0;
-                     gmsh_sys::gmshModelRemove(&mut ierr);
-                     check_main_error!(ierr, ())
-                 }
-             }
-
-            /// Set model to current model.
-            pub fn set_current(&self) -> GmshResult<()> {
-                unsafe {
-                    let mut ierr: c_int = 0;
-                    gmsh_sys::gmshModelSetCurrent(self.c_name.as_ptr(), &mut ierr);
-                    match ierr {
-                        0 => Ok(()),
-                        _ => Err(GmshError::Execution),
-                    }
-                }
+                self.model.remove()
+            }
+
+            // Set this model as current before a change to its geometry
+            fn modify(&mut self) -> GmshResult<()> {
+                self.model.mark_unsynchronized(impl_model!(@kernel $model_type));
+                self.model.set_current()
             }
 
             /// Synchronize the underlying CAD representation.
+            ///
+            /// Queries and meshing synchronize the model when needed, so this
+            /// is rarely necessary.
             pub fn synchronize(&mut self) -> GmshResult<()> {
-                self.set_current()?;
-                unsafe {
-                    let mut ierr: c_int = 0;
-                    let sync_fn = impl_model!(@kernel_prefix $model_type, synchronize);
-                    sync_fn(&mut ierr);
-                    check_model_error!(ierr, ())
-                }
+                self.model.set_current()?;
+                self.model.synchronize_kernel(impl_model!(@kernel $model_type))
             }
 
-            /// Mesh the model.
-            // probably should move this to a dedicated model class
-            // with an inner Option(Mesh) and Option(Geo)
-            pub fn generate_mesh(&mut self, dim: i32) -> GmshResult<()> {
-                self.set_current()?;
-                // TODO think about synchronize by default?
+            /// Finish building and synchronize the geometry into the model.
+            pub fn into_model(mut self) -> GmshResult<Model<'gmsh>> {
                 self.synchronize()?;
-                unsafe {
-                    let mut ierr: c_int = 0;
-                    gmsh_sys::gmshModelMeshGenerate(dim, &mut ierr);
-                    check_model_error!(ierr, ())
-                }
+                Ok(self.model)
+            }
+        }
+
+        impl<'gmsh> Deref for $model_type<'gmsh> {
+            type Target = Model<'gmsh>;
+
+            fn deref(&self) -> &Model<'gmsh> {
+                &self.model
+            }
+        }
+
+        impl<'gmsh> DerefMut for $model_type<'gmsh> {
+            fn deref_mut(&mut self) -> &mut Model<'gmsh> {
+                &mut self.model
             }
         }
     }
@@ -405,6 +616,7 @@ impl_model!(OccModel);
 
 mod geo;
 mod occ;
+pub use occ::BooleanOp;
 
 //    #[doc(hidden)]
 //    #[must_use]
@@ -625,10 +837,10 @@ mod occ;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 /// A point tag. Points are used to build larger shapes. 0D.
-pub struct PointTag(i32);
+pub struct PointTag(pub(crate) i32);
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 /// A curve tag, built from points. The curve type includes straight lines. 1D.
-pub struct CurveTag(i32);
+pub struct CurveTag(pub(crate) i32);
 
 /// Curves have a direction from start to end.
 impl Neg for CurveTag {
@@ -643,16 +855,16 @@ impl Neg for CurveTag {
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 /// A wire tag. Wires are built from curves. Wires are a path of multiple curves. 1.5D.
-pub struct WireTag(i32);
+pub struct WireTag(pub(crate) i32);
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 /// A surface tag. Surfaces are built from closed wires. 2D.
-pub struct SurfaceTag(i32);
+pub struct SurfaceTag(pub(crate) i32);
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 /// A shell tag. Shells are built from surface loops. 2.5D.
-pub struct ShellTag(i32);
+pub struct ShellTag(pub(crate) i32);
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 /// A volume tag. Volumes are built from closed shells. 3D.
-pub struct VolumeTag(i32);
+pub struct VolumeTag(pub(crate) i32);
 
 /// A trait for the different tags used by Gmsh.
 trait GmshTag {
@@ -684,6 +896,12 @@ impl GmshTag for SurfaceTag {
     }
 }
 
+impl GmshTag for VolumeTag {
+    fn to_raw(&self) -> i32 {
+        self.0
+    }
+}
+
 impl From<PointTag> for BasicShape {
     fn from(t: PointTag) -> BasicShape {
         BasicShape::Point(t)
@@ -696,6 +914,41 @@ impl From<CurveTag> for BasicShape {
     }
 }
 
+impl From<SurfaceTag> for BasicShape {
+    fn from(t: SurfaceTag) -> BasicShape {
+        BasicShape::Surface(t)
+    }
+}
+
+impl From<VolumeTag> for BasicShape {
+    fn from(t: VolumeTag) -> BasicShape {
+        BasicShape::Volume(t)
+    }
+}
+
+impl BasicShape {
+    /// The `(dim, tag)` pair Gmsh uses to identify an entity.
+    pub(crate) fn dim_tag(self) -> (i32, i32) {
+        match self {
+            BasicShape::Point(t) => (0, t.to_raw()),
+            BasicShape::Curve(t) => (1, t.to_raw()),
+            BasicShape::Surface(t) => (2, t.to_raw()),
+            BasicShape::Volume(t) => (3, t.to_raw()),
+        }
+    }
+
+    /// Make a shape from a `(dim, tag)` pair returned by Gmsh.
+    pub(crate) fn from_dim_tag(dim: i32, tag: i32) -> GmshResult<BasicShape> {
+        match dim {
+            0 => Ok(BasicShape::Point(PointTag(tag))),
+            1 => Ok(BasicShape::Curve(CurveTag(tag))),
+            2 => Ok(BasicShape::Surface(SurfaceTag(tag))),
+            3 => Ok(BasicShape::Volume(VolumeTag(tag))),
+            _ => Err(GmshError::CInterface),
+        }
+    }
+}
+
 /// Private module for sets of geometries passed and returned from functions.
 ///
 /// Gmsh operations can be on multiple known types. We use enums for a compile-time
@@ -703,12 +956,16 @@ impl From<CurveTag> for BasicShape {
 mod geometry_groups {
     use super::*;
 
-    #[derive(Debug, Copy, Clone)]
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
     /// The basic geometry types (points, curves, surfaces, and volumes).
     pub enum BasicShape {
+        /// A point.
         Point(PointTag),
+        /// A curve.
         Curve(CurveTag),
+        /// A surface.
         Surface(SurfaceTag),
+        /// A volume.
         Volume(VolumeTag),
     }
 
@@ -722,32 +979,26 @@ mod geometry_groups {
         Shell(ShellTag),
         Volume(VolumeTag),
     }
-
-    #[derive(Debug, Copy, Clone)]
-    /// Only curves or surfaces.
-    pub enum CurveOrSurface {
-        Curve(CurveTag),
-        Surface(SurfaceTag),
-    }
 }
 
-use geometry_groups::BasicShape;
-use geometry_groups::CurveOrSurface;
+pub use geometry_groups::BasicShape;
 
-type c_or_s = CurveOrSurface;
+/// A physical group tag. Physical groups associate information with
+/// geometry entities of the same dimension.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PhysicalGroupTag {
+    dim: i32,
+    tag: i32,
+}
 
-impl From<CurveTag> for c_or_s {
-    fn from(t: CurveTag) -> c_or_s {
-        CurveOrSurface::Curve(t)
+impl PhysicalGroupTag {
+    /// The dimension of the entities in the group.
+    pub fn dim(&self) -> i32 {
+        self.dim
     }
-}
 
-impl From<SurfaceTag> for CurveOrSurface {
-    fn from(t: SurfaceTag) -> CurveOrSurface {
-        CurveOrSurface::Surface(t)
+    // the tag Gmsh uses for the group
+    pub(crate) fn to_raw(self) -> i32 {
+        self.tag
     }
 }
-
-/// Associated geometry information.
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
-struct PhysicalGroupTag(i32);
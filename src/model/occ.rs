@@ -2,8 +2,11 @@
 
 use super::*;
 use crate::interface::occ as factory;
-use crate::{check_main_error, check_model_error, GmshError, GmshResult};
-
+use crate::interface::vec_from_raw;
+use crate::io::path_str;
+use crate::{check_model_error, get_cstring, trace_call, GmshError, GmshResult};
+use std::os::raw::c_void;
+use std::path::Path;
 
 include!("common_occ.rs");
 
@@ -16,7 +19,7 @@ impl<'gmsh> OccModel<'gmsh> {
         start_point: (f64, f64, f64),
         extents: (f64, f64, f64),
     ) -> GmshResult<VolumeTag> {
-        self.set_current()?;
+        self.modify()?;
         let mut ierr: c_int = 0;
         let automatic_tag: c_int = -1;
         unsafe {
@@ -30,7 +33,9 @@ impl<'gmsh> OccModel<'gmsh> {
                 automatic_tag,
                 &mut ierr,
             );
-            check_model_error!(ierr, VolumeTag(out_tag))
+            let result = check_model_error!(ierr, VolumeTag(out_tag));
+            trace_call!(self.name, "add_box({:?}, {:?})", start_point, extents => result);
+            result
         }
     }
 
@@ -88,7 +93,7 @@ impl<'gmsh> OccModel<'gmsh> {
         polar: (f64, f64),
         azimuth: f64,
     ) -> GmshResult<VolumeTag> {
-        self.set_current()?;
+        self.modify()?;
         unsafe {
             let mut ierr: c_int = 0;
             let automatic_tag: c_int = -1;
@@ -103,7 +108,9 @@ impl<'gmsh> OccModel<'gmsh> {
                 azimuth,
                 &mut ierr,
             );
-            check_model_error!(ierr, VolumeTag(out_tag))
+            let result = check_model_error!(ierr, VolumeTag(out_tag));
+            trace_call!(self.name, "add_sphere_section({:?}, {:?}, {:?}, {:?})", centroid, radius, polar, azimuth => result);
+            result
         }
     }
 
@@ -164,7 +171,7 @@ impl<'gmsh> OccModel<'gmsh> {
         radii: (f64, f64),
         angle: f64,
     ) -> GmshResult<VolumeTag> {
-        self.set_current()?;
+        self.modify()?;
         unsafe {
             let mut ierr: c_int = 0;
             let automatic_tag: c_int = -1;
@@ -178,7 +185,154 @@ impl<'gmsh> OccModel<'gmsh> {
                 angle,
                 &mut ierr,
             );
-            check_model_error!(ierr, VolumeTag(out_tag))
+            let result = check_model_error!(ierr, VolumeTag(out_tag));
+            trace_call!(self.name, "add_torus_section({:?}, {:?}, {:?})", centroid, radii, angle => result);
+            result
+        }
+    }
+
+    /// Import the shapes of a BREP, STEP or IGES file, and return the top-level entities.
+    pub fn import_shapes<P: AsRef<Path>>(&mut self, path: P) -> GmshResult<Vec<BasicShape>> {
+        self.modify()?;
+        let path = path.as_ref();
+        let c_path = get_cstring(path_str(path)?)?;
+        // an empty format lets Gmsh pick one from the file extension
+        let c_format = get_cstring("")?;
+        unsafe {
+            let mut ierr: c_int = 0;
+            let mut dim_tags: *mut c_int = std::ptr::null_mut();
+            let mut dim_tags_n: usize = 0;
+            factory::import_shapes(
+                c_path.as_ptr(),
+                &mut dim_tags,
+                &mut dim_tags_n,
+                1,
+                c_format.as_ptr(),
+                &mut ierr,
+            );
+            let dim_tags = vec_from_raw(dim_tags, dim_tags_n);
+            check_model_error!(ierr, ())?;
+            let result = shapes_from_dim_tags(&dim_tags);
+            trace_call!(self.name, "import_shapes({:?})", path => result);
+            result
+        }
+    }
+
+    /// Fuse the tool shapes into the objects. The inputs are removed.
+    pub fn fuse(
+        &mut self,
+        objects: &[BasicShape],
+        tools: &[BasicShape],
+    ) -> GmshResult<Vec<BasicShape>> {
+        self.boolean(BooleanOp::Fuse, objects, tools)
+    }
+
+    /// Cut the tool shapes out of the objects. The inputs are removed.
+    pub fn cut(
+        &mut self,
+        objects: &[BasicShape],
+        tools: &[BasicShape],
+    ) -> GmshResult<Vec<BasicShape>> {
+        self.boolean(BooleanOp::Cut, objects, tools)
+    }
+
+    /// Intersect the objects with the tool shapes. The inputs are removed.
+    pub fn intersect(
+        &mut self,
+        objects: &[BasicShape],
+        tools: &[BasicShape],
+    ) -> GmshResult<Vec<BasicShape>> {
+        self.boolean(BooleanOp::Intersect, objects, tools)
+    }
+
+    /// Fragment the objects and tools into conforming pieces. The inputs are removed.
+    pub fn fragment(
+        &mut self,
+        objects: &[BasicShape],
+        tools: &[BasicShape],
+    ) -> GmshResult<Vec<BasicShape>> {
+        self.boolean(BooleanOp::Fragment, objects, tools)
+    }
+
+    /// Apply a boolean operation, and return the resulting entities.
+    pub fn boolean(
+        &mut self,
+        op: BooleanOp,
+        objects: &[BasicShape],
+        tools: &[BasicShape],
+    ) -> GmshResult<Vec<BasicShape>> {
+        self.modify()?;
+        let mut object_dim_tags = dim_tags_from_shapes(objects);
+        let mut tool_dim_tags = dim_tags_from_shapes(tools);
+        let operation = match op {
+            BooleanOp::Fuse => factory::fuse,
+            BooleanOp::Cut => factory::cut,
+            BooleanOp::Intersect => factory::intersect,
+            BooleanOp::Fragment => factory::fragment,
+        };
+        unsafe {
+            let mut ierr: c_int = 0;
+            let mut dim_tags: *mut c_int = std::ptr::null_mut();
+            let mut dim_tags_n: usize = 0;
+            let mut map: *mut *mut c_int = std::ptr::null_mut();
+            let mut map_n: *mut usize = std::ptr::null_mut();
+            let mut map_nn: usize = 0;
+            let automatic_tag: c_int = -1;
+            operation(
+                object_dim_tags.as_mut_ptr(),
+                object_dim_tags.len(),
+                tool_dim_tags.as_mut_ptr(),
+                tool_dim_tags.len(),
+                &mut dim_tags,
+                &mut dim_tags_n,
+                &mut map,
+                &mut map_n,
+                &mut map_nn,
+                automatic_tag,
+                1,
+                1,
+                &mut ierr,
+            );
+            // the per-input map isn't exposed, but it still has to be freed
+            for entry in vec_from_raw(map, map_nn) {
+                gmsh_sys::gmshFree(entry as *mut c_void);
+            }
+            gmsh_sys::gmshFree(map_n as *mut c_void);
+            let dim_tags = vec_from_raw(dim_tags, dim_tags_n);
+            check_model_error!(ierr, ())?;
+            let result = shapes_from_dim_tags(&dim_tags);
+            trace_call!(self.name, "boolean({:?}, {:?}, {:?})", op, objects, tools => result);
+            result
         }
     }
 }
+
+/// The boolean operations of the `OpenCASCADE` kernel.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BooleanOp {
+    /// The union of objects and tools.
+    Fuse,
+    /// The objects without the tools.
+    Cut,
+    /// The common part of objects and tools.
+    Intersect,
+    /// All pieces of objects and tools, with shared boundaries.
+    Fragment,
+}
+
+fn dim_tags_from_shapes(shapes: &[BasicShape]) -> Vec<c_int> {
+    shapes
+        .iter()
+        .flat_map(|shape| {
+            let (dim, tag) = shape.dim_tag();
+            vec![dim, tag]
+        })
+        .collect()
+}
+
+fn shapes_from_dim_tags(dim_tags: &[c_int]) -> GmshResult<Vec<BasicShape>> {
+    dim_tags
+        .chunks_exact(2)
+        .map(|dt| BasicShape::from_dim_tag(dt[0], dt[1]))
+        .collect()
+}
@@ -0,0 +1,129 @@
+//! Physical groups.
+//!
+//! Physical groups collect entities of the same dimension under one tag and an
+//! optional name, e.g. for materials or boundary conditions.
+//! ```
+//! # use rgmsh::{Gmsh, GmshResult};
+//! # fn main() -> GmshResult<()> {
+//! # let gmsh = Gmsh::initialize()?;
+//! let mut geom = gmsh.create_native_model("model")?;
+//! let p1 = geom.add_point(0., 0., 0.)?;
+//! let p2 = geom.add_point(1., 0., 0.)?;
+//! let l1 = geom.add_line(p1, p2)?;
+//!
+//! let inlet = geom.add_physical_group(&[l1])?;
+//! geom.set_physical_name(inlet, "inlet")?;
+//! assert!(geom.get_physical_name(inlet)? == "inlet");
+//! # Ok(())
+//! # }
+//! ```
+
+use super::*;
+use crate::interface::{string_from_raw, vec_from_raw};
+use crate::{check_model_error, get_cstring, GmshError, GmshResult};
+
+// Physical group methods, for models built with either kernel
+impl<'gmsh> Model<'gmsh> {
+    /// Add a physical group from entities of the same dimension.
+    pub fn add_physical_group<T: Into<BasicShape> + Copy>(
+        &mut self,
+        entities: &[T],
+    ) -> GmshResult<PhysicalGroupTag> {
+        let dim_tags: Vec<(i32, i32)> = entities.iter().map(|&e| e.into().dim_tag()).collect();
+        let dim = match dim_tags.first() {
+            Some(&(dim, _)) => dim,
+            None => return Err(GmshError::ModelBadInput),
+        };
+        if dim_tags.iter().any(|&(d, _)| d != dim) {
+            return Err(GmshError::ModelBadInput);
+        }
+        self.sync_current()?;
+        let mut raw_tags: Vec<c_int> = dim_tags.iter().map(|&(_, t)| t).collect();
+        let auto_number = -1;
+        unsafe {
+            let mut ierr: c_int = 0;
+            let out_tag = gmsh_sys::gmshModelAddPhysicalGroup(
+                dim,
+                raw_tags.as_mut_ptr(),
+                raw_tags.len(),
+                auto_number,
+                &mut ierr,
+            );
+            check_model_error!(ierr, PhysicalGroupTag { dim, tag: out_tag })
+        }
+    }
+
+    /// Name a physical group.
+    pub fn set_physical_name(&mut self, group: PhysicalGroupTag, name: &str) -> GmshResult<()> {
+        self.sync_current()?;
+        let c_name = get_cstring(name)?;
+        unsafe {
+            let mut ierr: c_int = 0;
+            gmsh_sys::gmshModelSetPhysicalName(group.dim, group.tag, c_name.as_ptr(), &mut ierr);
+            check_model_error!(ierr, ())
+        }
+    }
+
+    /// Get the name of a physical group.
+    pub fn get_physical_name(&self, group: PhysicalGroupTag) -> GmshResult<String> {
+        self.sync_current()?;
+        unsafe {
+            let mut ierr: c_int = 0;
+            let mut name: *mut std::os::raw::c_char = std::ptr::null_mut();
+            gmsh_sys::gmshModelGetPhysicalName(group.dim, group.tag, &mut name, &mut ierr);
+            let name = string_from_raw(name)?;
+            check_model_error!(ierr, name)
+        }
+    }
+
+    /// Get all physical groups in the model.
+    pub fn get_physical_groups(&self) -> GmshResult<Vec<PhysicalGroupTag>> {
+        self.sync_current()?;
+        unsafe {
+            let mut ierr: c_int = 0;
+            let mut dim_tags: *mut c_int = std::ptr::null_mut();
+            let mut dim_tags_n: usize = 0;
+            let all_dims = -1;
+            gmsh_sys::gmshModelGetPhysicalGroups(
+                &mut dim_tags,
+                &mut dim_tags_n,
+                all_dims,
+                &mut ierr,
+            );
+            let dim_tags = vec_from_raw(dim_tags, dim_tags_n);
+            let groups = dim_tags
+                .chunks_exact(2)
+                .map(|dt| PhysicalGroupTag {
+                    dim: dt[0],
+                    tag: dt[1],
+                })
+                .collect();
+            check_model_error!(ierr, groups)
+        }
+    }
+
+    /// Get the entities in a physical group.
+    pub fn get_entities_for_physical_group(
+        &self,
+        group: PhysicalGroupTag,
+    ) -> GmshResult<Vec<BasicShape>> {
+        self.sync_current()?;
+        unsafe {
+            let mut ierr: c_int = 0;
+            let mut tags: *mut c_int = std::ptr::null_mut();
+            let mut tags_n: usize = 0;
+            gmsh_sys::gmshModelGetEntitiesForPhysicalGroup(
+                group.dim,
+                group.tag,
+                &mut tags,
+                &mut tags_n,
+                &mut ierr,
+            );
+            let tags = vec_from_raw(tags, tags_n);
+            check_model_error!(ierr, ())?;
+            tags.into_iter()
+                .map(|t| BasicShape::from_dim_tag(group.dim, t))
+                .collect()
+        }
+    }
+}
@@ -0,0 +1,99 @@
+//! Quad-dominant meshing controls.
+//!
+//! Gmsh makes quadrangles by recombining triangles, either on individual
+//! surfaces or everywhere with the `Mesh.RecombineAll` option.
+//!
+//! Cross fields and quasi-structured quad meshing only arrived after Gmsh 4.4.1,
+//! the version `gmsh-sys` binds, so they aren't available here.
+//! ```
+//! # use rgmsh::{Gmsh, GmshResult};
+//! # use rgmsh::model::{MeshAlgorithm2D, RecombinationAlgorithm};
+//! # fn main() -> GmshResult<()> {
+//! let gmsh = Gmsh::initialize()?;
+//! gmsh.set_mesh_algorithm_2d(MeshAlgorithm2D::FrontalDelaunayQuads)?;
+//! gmsh.set_recombination_algorithm(RecombinationAlgorithm::BlossomFullQuad)?;
+//!
+//! let mut geom = gmsh.create_occ_model("shell")?;
+//! let p1 = geom.add_point(0., 0., 0.)?;
+//! let p2 = geom.add_point(1., 0., 0.)?;
+//! let p3 = geom.add_point(1., 1., 0.)?;
+//! let p4 = geom.add_point(0., 1., 0.)?;
+//! let l1 = geom.add_line(p1, p2)?;
+//! let l2 = geom.add_line(p2, p3)?;
+//! let l3 = geom.add_line(p3, p4)?;
+//! let l4 = geom.add_line(p4, p1)?;
+//! let cl = geom.add_curve_loop(&[l1, l2, l3, l4])?;
+//! let s = geom.add_plane_surface(cl)?;
+//!
+//! // pure quadrangles on this surface
+//! geom.set_recombine(s)?;
+//! geom.generate_mesh(2)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use super::*;
+use crate::options;
+use crate::{check_model_error, GmshError, GmshResult};
+
+/// 2D meshing algorithms (`Mesh.Algorithm`).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MeshAlgorithm2D {
+    /// Adaptive meshing.
+    MeshAdapt = 1,
+    /// Gmsh's automatic choice.
+    Automatic = 2,
+    /// Delaunay triangulation.
+    Delaunay = 5,
+    /// Frontal-Delaunay triangulation.
+    FrontalDelaunay = 6,
+    /// The BAMG anisotropic mesher.
+    Bamg = 7,
+    /// Frontal-Delaunay with right-angled triangles, for recombination into quads.
+    FrontalDelaunayQuads = 8,
+    /// Packing of parallelograms.
+    PackingOfParallelograms = 9,
+}
+
+/// Algorithms for recombining triangles into quadrangles (`Mesh.RecombinationAlgorithm`).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RecombinationAlgorithm {
+    /// Simple greedy recombination, leaving some triangles.
+    Simple = 0,
+    /// Blossom perfect matching, leaving some triangles.
+    Blossom = 1,
+    /// Simple recombination followed by subdivision into quads only.
+    SimpleFullQuad = 2,
+    /// Blossom recombination followed by subdivision into quads only.
+    BlossomFullQuad = 3,
+}
+
+impl Gmsh {
+    /// Set the 2D meshing algorithm for all surfaces.
+    pub fn set_mesh_algorithm_2d(&self, algorithm: MeshAlgorithm2D) -> GmshResult<()> {
+        self.set_option(options::Mesh::Algorithm, algorithm as i32)
+    }
+
+    /// Set the algorithm used to recombine triangles into quadrangles.
+    pub fn set_recombination_algorithm(&self, algorithm: RecombinationAlgorithm) -> GmshResult<()> {
+        self.set_option(options::Mesh::RecombinationAlgorithm, algorithm as i32)
+    }
+
+    /// Recombine the triangles of all surfaces after meshing.
+    pub fn set_recombine_all(&self, recombine: bool) -> GmshResult<()> {
+        self.set_option(options::Mesh::RecombineAll, if recombine { 1. } else { 0. })
+    }
+}
+
+// Quad meshing methods, for models built with either kernel
+impl<'gmsh> Model<'gmsh> {
+    /// Recombine the triangles of a surface mesh into quadrangles when meshing.
+    pub fn set_recombine(&mut self, surface: SurfaceTag) -> GmshResult<()> {
+        self.sync_current()?;
+        unsafe {
+            let mut ierr: c_int = 0;
+            gmsh_sys::gmshModelMeshSetRecombine(2, surface.to_raw(), &mut ierr);
+            check_model_error!(ierr, ())
+        }
+    }
+}
@@ -0,0 +1,281 @@
+//! Mesh quality metrics.
+//!
+//! Check the quality of a generated mesh before handing it to a solver.
+//! ```
+//! # use rgmsh::{Gmsh, GmshResult};
+//! # use rgmsh::model::QualityMeasure;
+//! # fn main() -> GmshResult<()> {
+//! # let gmsh = Gmsh::initialize()?;
+//! let mut geom = gmsh.create_occ_model("model")?;
+//! geom.add_box((0., 0., 0.), (1., 1., 1.))?;
+//! geom.generate_mesh(3)?;
+//!
+//! let stats = geom.mesh_statistics(3, QualityMeasure::MinSICN)?;
+//! assert!(stats.min_quality > 0., "inverted elements!");
+//! println!("{}", stats);
+//! # Ok(())
+//! # }
+//! ```
+
+use super::*;
+use crate::plugin::AnalyseMeshQuality;
+use crate::{GmshError, GmshResult};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Element quality measures.
+///
+/// Gmsh 4.4.1 computes these with the `AnalyseMeshQuality` plugin, which
+/// doesn't offer the other Gmsh quality measures, e.g. gamma.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum QualityMeasure {
+    /// Minimum signed inverse condition number, from -1 (inverted) to 1 (ideal).
+    MinSICN,
+    /// Minimum signed inverse gradient error, from -1 (inverted) to 1 (ideal).
+    MinSIGE,
+}
+
+/// A histogram of element qualities with equal-width bins.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QualityHistogram {
+    /// The bin edges, one more than the number of bins.
+    pub edges: Vec<f64>,
+    /// The number of elements in each bin.
+    pub counts: Vec<usize>,
+}
+
+/// A low-quality mesh element.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct WorstElement {
+    /// The element tag.
+    pub tag: usize,
+    /// The element type.
+    pub element_type: ElementType,
+    /// The element quality.
+    pub quality: f64,
+    /// Where the element is in the model.
+    pub barycenter: (f64, f64, f64),
+}
+
+/// A summary of the element qualities in a mesh.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeshStatistics {
+    /// The quality measure used.
+    pub measure: QualityMeasure,
+    /// The number of elements of each type.
+    pub element_counts: Vec<(ElementType, usize)>,
+    /// The minimum element quality.
+    pub min_quality: f64,
+    /// The mean element quality.
+    pub mean_quality: f64,
+    /// The maximum element quality.
+    pub max_quality: f64,
+    /// The distribution of element qualities.
+    pub histogram: QualityHistogram,
+    /// The lowest-quality elements, worst first.
+    pub worst_elements: Vec<WorstElement>,
+}
+
+impl MeshStatistics {
+    /// The number of histogram bins.
+    pub const HISTOGRAM_BINS: usize = 10;
+    /// The number of worst elements kept.
+    pub const NUM_WORST_ELEMENTS: usize = 10;
+
+    /// The total number of elements.
+    pub fn num_elements(&self) -> usize {
+        self.element_counts.iter().map(|&(_, count)| count).sum()
+    }
+
+    // summarize the qualities of a set of elements
+    fn from_elements(
+        measure: QualityMeasure,
+        element_counts: Vec<(ElementType, usize)>,
+        mut elements: Vec<WorstElement>,
+    ) -> GmshResult<MeshStatistics> {
+        if elements.is_empty() {
+            return Err(GmshError::ModelLookup);
+        }
+
+        let qualities = elements.iter().map(|e| e.quality);
+        let min_quality = qualities.clone().fold(f64::INFINITY, f64::min);
+        let max_quality = qualities.clone().fold(f64::NEG_INFINITY, f64::max);
+        let mean_quality = qualities.clone().sum::<f64>() / elements.len() as f64;
+
+        let bins = Self::HISTOGRAM_BINS;
+        let width = (max_quality - min_quality) / bins as f64;
+        let edges = (0..=bins).map(|i| min_quality + i as f64 * width).collect();
+        let mut counts = vec![0; bins];
+        for q in qualities {
+            let bin = if width > 0. {
+                ((q - min_quality) / width) as usize
+            } else {
+                0
+            };
+            counts[bin.min(bins - 1)] += 1;
+        }
+
+        elements.sort_by(|a, b| {
+            a.quality
+                .partial_cmp(&b.quality)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        elements.truncate(Self::NUM_WORST_ELEMENTS);
+
+        Ok(MeshStatistics {
+            measure,
+            element_counts,
+            min_quality,
+            mean_quality,
+            max_quality,
+            histogram: QualityHistogram { edges, counts },
+            worst_elements: elements,
+        })
+    }
+}
+
+impl fmt::Display for MeshStatistics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} elements", self.num_elements())?;
+        for (element_type, count) in self.element_counts.iter() {
+            writeln!(f, "  {:?}: {}", element_type, count)?;
+        }
+        writeln!(
+            f,
+            "{:?}: min {:.4}, mean {:.4}, max {:.4}",
+            self.measure, self.min_quality, self.mean_quality, self.max_quality
+        )?;
+        let bins = self
+            .histogram
+            .edges
+            .windows(2)
+            .zip(self.histogram.counts.iter());
+        for (edges, count) in bins {
+            writeln!(f, "  [{:.4}, {:.4}]: {}", edges[0], edges[1], count)?;
+        }
+        writeln!(f, "worst elements:")?;
+        for e in self.worst_elements.iter() {
+            writeln!(
+                f,
+                "  {} ({:?}) at {:?}: {:.4}",
+                e.tag, e.element_type, e.barycenter, e.quality
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// Quality methods, for models built with either kernel
+impl<'gmsh> Model<'gmsh> {
+    /// Get the quality of each mesh element of dimension `dim`, as
+    /// `(element tag, quality)` pairs.
+    ///
+    /// This runs the `AnalyseMeshQuality` plugin and reads back the view it
+    /// makes, then removes the view.
+    pub fn element_qualities(
+        &self,
+        dim: i32,
+        measure: QualityMeasure,
+    ) -> GmshResult<Vec<(usize, f64)>> {
+        self.sync_current()?;
+        let plugin = AnalyseMeshQuality {
+            jacobian_determinant: false,
+            ige_measure: measure == QualityMeasure::MinSIGE,
+            icn_measure: measure == QualityMeasure::MinSICN,
+            dim,
+        };
+        let view = self
+            .gmsh
+            .run_plugin(&plugin)?
+            .ok_or(GmshError::ModelLookup)?;
+        let data = self.gmsh.get_model_data(view, 0);
+        self.gmsh.remove_view(view)?;
+        let data = data?;
+        // one value per element
+        data.tags
+            .into_iter()
+            .zip(data.data)
+            .map(|(tag, values)| match values.first() {
+                Some(&quality) => Ok((tag, quality)),
+                None => Err(GmshError::CInterface),
+            })
+            .collect()
+    }
+
+    /// Summarize the quality of all mesh elements of dimension `dim`.
+    ///
+    /// Fails with `ModelLookup` if there are no elements of that dimension.
+    pub fn mesh_statistics(&self, dim: i32, measure: QualityMeasure) -> GmshResult<MeshStatistics> {
+        let all_entities = -1;
+        let element_types = self.get_element_types_gen(dim, all_entities)?;
+        if element_types.is_empty() {
+            return Err(GmshError::ModelLookup);
+        }
+        let qualities: HashMap<usize, f64> =
+            self.element_qualities(dim, measure)?.into_iter().collect();
+        let mut element_counts = Vec::new();
+        let mut elements = Vec::new();
+        for element_type in element_types {
+            let tags = self
+                .get_elements_by_type_gen(element_type, all_entities)?
+                .tags;
+            let barycenters = self.get_barycenters_gen(element_type, all_entities, false)?;
+            // one barycenter per element
+            if barycenters.len() != 3 * tags.len() {
+                return Err(GmshError::CInterface);
+            }
+            element_counts.push((element_type, tags.len()));
+            for (&tag, b) in tags.iter().zip(barycenters.chunks_exact(3)) {
+                let quality = *qualities.get(&tag).ok_or(GmshError::CInterface)?;
+                elements.push(WorstElement {
+                    tag,
+                    element_type,
+                    quality,
+                    barycenter: (b[0], b[1], b[2]),
+                });
+            }
+        }
+        MeshStatistics::from_elements(measure, element_counts, elements)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn element(tag: usize, quality: f64) -> WorstElement {
+        WorstElement {
+            tag,
+            element_type: ElementType::Tet4,
+            quality,
+            barycenter: (0., 0., 0.),
+        }
+    }
+
+    #[test]
+    pub fn summarize_qualities() -> GmshResult<()> {
+        let elements = (1..=20).map(|i| element(i, i as f64 / 20.)).collect();
+        let stats = MeshStatistics::from_elements(
+            QualityMeasure::MinSICN,
+            vec![(ElementType::Tet4, 20)],
+            elements,
+        )?;
+
+        assert!(stats.num_elements() == 20);
+        assert!(stats.min_quality == 0.05 && stats.max_quality == 1.);
+        assert!((stats.mean_quality - 0.525).abs() < 1e-12);
+        assert!(stats.histogram.counts.iter().sum::<usize>() == 20);
+        assert!(stats.worst_elements.len() == MeshStatistics::NUM_WORST_ELEMENTS);
+        assert!(stats.worst_elements[0].tag == 1);
+        Ok(())
+    }
+
+    #[test]
+    pub fn no_elements_is_an_error() {
+        let stats = MeshStatistics::from_elements(QualityMeasure::MinSICN, Vec::new(), Vec::new());
+        match stats {
+            Err(GmshError::ModelLookup) => (),
+            _ => panic!(),
+        }
+    }
+}
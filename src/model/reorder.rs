@@ -0,0 +1,407 @@
+//! Mesh renumbering and reordering.
+//!
+//! The numbering of nodes and elements sets the bandwidth of the matrices a
+//! solver assembles. This module computes orderings on extracted mesh data:
+//! * reverse Cuthill–McKee, which minimizes the bandwidth of the connectivity graph,
+//! * Hilbert curve ordering, which keeps spatially close points close in memory.
+//!
+//! Node orderings are pushed back into Gmsh with `reorder_nodes`, and element
+//! orderings with `reorder_elements` followed by `renumber_elements`.
+//! ```
+//! # use rgmsh::{Gmsh, GmshResult};
+//! # use rgmsh::model::{reorder, ElementType};
+//! # fn main() -> GmshResult<()> {
+//! # let gmsh = Gmsh::initialize()?;
+//! let mut geom = gmsh.create_occ_model("model")?;
+//! let b = geom.add_box((0., 0., 0.), (1., 1., 1.))?;
+//! geom.generate_mesh(3)?;
+//!
+//! let tets = geom.get_elements_by_type(ElementType::Tet4, b)?;
+//! let ordering = reorder::element_ordering_rcm(&tets);
+//! geom.reorder_elements(ElementType::Tet4, b, &ordering)?;
+//! geom.renumber_elements()?;
+//!
+//! let tets = geom.get_elements_by_type(ElementType::Tet4, b)?;
+//! let node_order = reorder::node_ordering_rcm(&[tets]);
+//! geom.reorder_nodes(&node_order)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use super::*;
+use crate::{check_model_error, GmshError, GmshResult};
+use std::collections::{HashMap, VecDeque};
+
+/// Order the nodes of a set of elements by reverse Cuthill–McKee.
+/// Returns the node tags in their new order, as `reorder_nodes` expects.
+pub fn node_ordering_rcm(elements: &[MeshElements]) -> Vec<usize> {
+    // map node tags to graph vertices
+    let mut tags = Vec::new();
+    let mut index = HashMap::new();
+    for block in elements {
+        for &tag in block.node_tags.iter() {
+            index.entry(tag).or_insert_with(|| {
+                tags.push(tag);
+                tags.len() - 1
+            });
+        }
+    }
+
+    // nodes are adjacent if they share an element
+    let mut adjacency = vec![Vec::new(); tags.len()];
+    for block in elements {
+        for nodes in element_nodes(block) {
+            for &a in nodes {
+                for &b in nodes {
+                    if a != b {
+                        adjacency[index[&a]].push(index[&b]);
+                    }
+                }
+            }
+        }
+    }
+
+    reverse_cuthill_mckee(adjacency)
+        .into_iter()
+        .map(|v| tags[v])
+        .collect()
+}
+
+/// Order the elements of one type by reverse Cuthill–McKee.
+/// Returns element positions in their new order, as `reorder_elements` expects.
+pub fn element_ordering_rcm(elements: &MeshElements) -> Vec<usize> {
+    // elements are adjacent if they share a node
+    let mut node_elements: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (e, nodes) in element_nodes(elements).enumerate() {
+        for &n in nodes {
+            node_elements.entry(n).or_default().push(e);
+        }
+    }
+
+    let mut adjacency = vec![Vec::new(); elements.tags.len()];
+    for shared in node_elements.values() {
+        for &a in shared {
+            for &b in shared {
+                if a != b {
+                    adjacency[a].push(b);
+                }
+            }
+        }
+    }
+
+    reverse_cuthill_mckee(adjacency)
+}
+
+/// Order points along a 3D Hilbert curve. Takes coordinates of shape
+/// `num_points × 3`, like node coordinates or element barycenters, and returns
+/// point positions in their new order.
+pub fn hilbert_ordering(coords: &[f64]) -> Vec<usize> {
+    const BITS: u32 = 21;
+    let points: Vec<&[f64]> = coords.chunks_exact(3).collect();
+
+    // bounding box
+    let mut min = [f64::INFINITY; 3];
+    let mut max = [f64::NEG_INFINITY; 3];
+    for p in points.iter() {
+        for axis in 0..3 {
+            min[axis] = min[axis].min(p[axis]);
+            max[axis] = max[axis].max(p[axis]);
+        }
+    }
+
+    // quantize to integer grid coordinates
+    let cells = ((1u64 << BITS) - 1) as f64;
+    let mut keys: Vec<(u64, usize)> = points
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let mut grid = [0u32; 3];
+            for axis in 0..3 {
+                let extent = max[axis] - min[axis];
+                if extent > 0. {
+                    grid[axis] = ((p[axis] - min[axis]) / extent * cells) as u32;
+                }
+            }
+            (hilbert_index(grid, BITS), i)
+        })
+        .collect();
+
+    keys.sort();
+    keys.into_iter().map(|(_, i)| i).collect()
+}
+
+// the nodes of each element in a block
+fn element_nodes(elements: &MeshElements) -> std::slice::Chunks<'_, usize> {
+    let nodes_per_element = match elements.tags.len() {
+        0 => 1,
+        n => std::cmp::max(elements.node_tags.len() / n, 1),
+    };
+    elements.node_tags.chunks(nodes_per_element)
+}
+
+// Reverse Cuthill–McKee ordering of a graph given as adjacency lists
+fn reverse_cuthill_mckee(mut adjacency: Vec<Vec<usize>>) -> Vec<usize> {
+    for neighbours in adjacency.iter_mut() {
+        neighbours.sort();
+        neighbours.dedup();
+    }
+    let degree: Vec<usize> = adjacency.iter().map(|n| n.len()).collect();
+
+    // start each connected component from a vertex of minimum degree
+    let mut starts: Vec<usize> = (0..adjacency.len()).collect();
+    starts.sort_by_key(|&v| degree[v]);
+
+    let mut visited = vec![false; adjacency.len()];
+    let mut order = Vec::with_capacity(adjacency.len());
+    for start in starts {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        let mut queue = VecDeque::new();
+        queue.push_back(start);
+        while let Some(v) = queue.pop_front() {
+            order.push(v);
+            let mut next: Vec<usize> = adjacency[v]
+                .iter()
+                .cloned()
+                .filter(|&n| !visited[n])
+                .collect();
+            next.sort_by_key(|&n| degree[n]);
+            for n in next {
+                visited[n] = true;
+                queue.push_back(n);
+            }
+        }
+    }
+
+    order.reverse();
+    order
+}
+
+// Position of a point along a 3D Hilbert curve of order `bits`.
+// From J. Skilling, "Programming the Hilbert curve", AIP Conf. Proc. 707 (2004).
+fn hilbert_index(mut x: [u32; 3], bits: u32) -> u64 {
+    let top = 1u32 << (bits - 1);
+
+    // inverse undo excess work
+    let mut q = top;
+    while q > 1 {
+        let p = q - 1;
+        for i in 0..3 {
+            if x[i] & q != 0 {
+                x[0] ^= p;
+            } else {
+                let t = (x[0] ^ x[i]) & p;
+                x[0] ^= t;
+                x[i] ^= t;
+            }
+        }
+        q >>= 1;
+    }
+
+    // Gray encode
+    x[1] ^= x[0];
+    x[2] ^= x[1];
+    let mut t = 0;
+    let mut q = top;
+    while q > 1 {
+        if x[2] & q != 0 {
+            t ^= q - 1;
+        }
+        q >>= 1;
+    }
+    for xi in x.iter_mut() {
+        *xi ^= t;
+    }
+
+    // interleave the transposed bits into a single index
+    let mut index = 0u64;
+    for b in (0..bits).rev() {
+        for xi in x.iter() {
+            index = (index << 1) | u64::from((xi >> b) & 1);
+        }
+    }
+    index
+}
+
+// Renumbering methods, for models built with either kernel
+impl<'gmsh> Model<'gmsh> {
+    /// Renumber the mesh nodes continuously from 1.
+    pub fn renumber_nodes(&mut self) -> GmshResult<()> {
+        self.sync_current()?;
+        unsafe {
+            let mut ierr: c_int = 0;
+            gmsh_sys::gmshModelMeshRenumberNodes(&mut ierr);
+            check_model_error!(ierr, ())
+        }
+    }
+
+    /// Renumber the mesh elements continuously from 1, following their
+    /// current order.
+    pub fn renumber_elements(&mut self) -> GmshResult<()> {
+        self.sync_current()?;
+        unsafe {
+            let mut ierr: c_int = 0;
+            gmsh_sys::gmshModelMeshRenumberElements(&mut ierr);
+            check_model_error!(ierr, ())
+        }
+    }
+
+    /// Renumber the mesh nodes following `ordering`, a list of node tags:
+    /// node `ordering[i]` gets the tag `i + 1`. Nodes missing from `ordering`
+    /// are numbered after it, in their current order.
+    ///
+    /// Gmsh can't retag nodes in place, so the mesh is read back, cleared and
+    /// added again with the new tags. Element tags are kept.
+    pub fn reorder_nodes(&mut self, ordering: &[usize]) -> GmshResult<()> {
+        let mut new_tags = HashMap::with_capacity(ordering.len());
+        for (i, &node) in ordering.iter().enumerate() {
+            if new_tags.insert(node, i + 1).is_some() {
+                return Err(GmshError::ModelBadInput);
+            }
+        }
+
+        // read back the nodes and elements of each entity
+        let mut meshes = Vec::new();
+        for entity in self.get_entities(None)? {
+            let nodes = self.get_nodes(entity, false)?;
+            let (dim, tag) = entity.dim_tag();
+            let mut elements = Vec::new();
+            for element_type in self.get_element_types_gen(dim, tag)? {
+                elements.push(self.get_elements_by_type_gen(element_type, tag)?);
+            }
+            meshes.push((entity, nodes, elements));
+        }
+
+        let num_nodes: usize = meshes.iter().map(|(_, nodes, _)| nodes.tags.len()).sum();
+        let mut next_tag = ordering.len();
+        for (_, nodes, _) in meshes.iter() {
+            for &node in nodes.tags.iter() {
+                new_tags.entry(node).or_insert_with(|| {
+                    next_tag += 1;
+                    next_tag
+                });
+            }
+        }
+        // every tag in the ordering has to be a node of the mesh
+        if new_tags.len() != num_nodes {
+            return Err(GmshError::ModelBadInput);
+        }
+
+        self.clear_mesh()?;
+        for (entity, nodes, _) in meshes.iter() {
+            let tags: Vec<usize> = nodes.tags.iter().map(|n| new_tags[n]).collect();
+            self.add_nodes(*entity, &tags, &nodes.coords, &nodes.parametric_coords)?;
+        }
+        for (entity, _, elements) in meshes.iter() {
+            for block in elements {
+                let node_tags: Vec<usize> = block.node_tags.iter().map(|n| new_tags[n]).collect();
+                self.add_elements_by_type(*entity, block.element_type, &block.tags, &node_tags)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reorder the elements of a type on an entity. `ordering[i]` is the
+    /// current position of the element that moves to position `i`.
+    pub fn reorder_elements<T: Into<BasicShape>>(
+        &mut self,
+        element_type: ElementType,
+        entity: T,
+        ordering: &[usize],
+    ) -> GmshResult<()> {
+        self.sync_current()?;
+        let (_, tag) = entity.into().dim_tag();
+        unsafe {
+            let mut ierr: c_int = 0;
+            // Gmsh only reads the ordering
+            gmsh_sys::gmshModelMeshReorderElements(
+                element_type.to_raw(),
+                tag,
+                ordering.as_ptr() as *mut usize,
+                ordering.len(),
+                &mut ierr,
+            );
+            check_model_error!(ierr, ())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the largest distance between the positions of adjacent vertices
+    fn bandwidth(order: &[usize], edges: &[(usize, usize)]) -> usize {
+        let mut position = vec![0; order.len()];
+        for (i, &v) in order.iter().enumerate() {
+            position[v] = i;
+        }
+        edges
+            .iter()
+            .map(|&(a, b)| (position[a] as isize - position[b] as isize).unsigned_abs())
+            .max()
+            .unwrap_or(0)
+    }
+
+    #[test]
+    pub fn rcm_untangles_a_path() {
+        // a path 0 - 5 - 2 - 7 - 1 - 6 - 3 - 4 with scrambled numbering
+        let path = [0, 5, 2, 7, 1, 6, 3, 4];
+        let edges: Vec<(usize, usize)> = path.windows(2).map(|w| (w[0], w[1])).collect();
+        let mut adjacency = vec![Vec::new(); path.len()];
+        for &(a, b) in edges.iter() {
+            adjacency[a].push(b);
+            adjacency[b].push(a);
+        }
+
+        let order = reverse_cuthill_mckee(adjacency);
+        assert!(order.len() == path.len());
+        assert!(bandwidth(&order, &edges) == 1);
+    }
+
+    #[test]
+    pub fn rcm_orders_element_nodes() {
+        // three line elements in a chain: 10 - 30 - 20 - 40
+        let lines = MeshElements {
+            element_type: ElementType::Line2,
+            tags: vec![1, 2, 3],
+            node_tags: vec![10, 30, 30, 20, 20, 40],
+        };
+
+        let nodes = node_ordering_rcm(std::slice::from_ref(&lines));
+        assert!(nodes == vec![10, 30, 20, 40] || nodes == vec![40, 20, 30, 10]);
+
+        let elements = element_ordering_rcm(&lines);
+        assert!(elements == vec![0, 1, 2] || elements == vec![2, 1, 0]);
+    }
+
+    #[test]
+    pub fn hilbert_visits_neighbours() {
+        // the corners of a cube, in scrambled order
+        let corners = [
+            (1., 1., 1.),
+            (0., 0., 0.),
+            (1., 0., 1.),
+            (0., 1., 0.),
+            (1., 1., 0.),
+            (0., 0., 1.),
+            (1., 0., 0.),
+            (0., 1., 1.),
+        ];
+        let coords: Vec<f64> = corners
+            .iter()
+            .flat_map(|&(x, y, z)| vec![x, y, z])
+            .collect();
+
+        let order = hilbert_ordering(&coords);
+        assert!(order.len() == corners.len());
+        // each step along the curve moves along a single cube edge
+        for w in order.windows(2) {
+            let (a, b) = (corners[w[0]], corners[w[1]]);
+            let dist = (a.0 - b.0).abs() + (a.1 - b.1).abs() + (a.2 - b.2).abs();
+            assert!(dist == 1.);
+        }
+    }
+}
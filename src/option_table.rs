@@ -0,0 +1,136 @@
+// The typed option catalogue, in the `options!` format. scripts/gen_options.py
+// generates it from Gmsh's option table; the entries below are the commonly
+// used options, to be replaced by the generated table from the Gmsh sources.
+options! {
+    /// General options.
+    General {
+        /// Print messages on the terminal.
+        Terminal: number = 0.;
+        /// Level of information printed: 0 for silent up to 99 for debug.
+        Verbosity: number = 5.;
+        /// Maximum number of threads for multithreaded algorithms, 0 for the system default.
+        NumThreads: number = 1.;
+        /// Show the axes: 0 for none, up to 5 for a full grid.
+        Axes: number = 0.;
+        /// Enable expert mode, which disables some safety checks.
+        ExpertMode: number = 0.;
+        /// Gmsh version (read-only).
+        Version: string = "";
+        /// Gmsh build information (read-only).
+        BuildInfo: string = "";
+        /// Background color.
+        BackgroundColor["Color.Background"]: color = (255, 255, 255);
+        /// Foreground color.
+        ForegroundColor["Color.Foreground"]: color = (85, 85, 85);
+        /// Text color.
+        TextColor["Color.Text"]: color = (0, 0, 0);
+    }
+
+    /// Geometry options.
+    Geometry {
+        /// Geometrical tolerance.
+        Tolerance: number = 1e-8;
+        /// Remove duplicate entities of the built-in kernel automatically.
+        AutoCoherence: number = 1.;
+        /// Display the points.
+        Points: number = 1.;
+        /// Display the curves.
+        Curves: number = 1.;
+        /// Display the surfaces.
+        Surfaces: number = 0.;
+        /// Display the volumes.
+        Volumes: number = 0.;
+        /// Fix degenerated edges and faces of imported OpenCASCADE shapes.
+        OCCFixDegenerated: number = 0.;
+        /// Fix small edges of imported OpenCASCADE shapes.
+        OCCFixSmallEdges: number = 0.;
+        /// Fix small faces of imported OpenCASCADE shapes.
+        OCCFixSmallFaces: number = 0.;
+        /// Sew the faces of imported OpenCASCADE shapes.
+        OCCSewFaces: number = 0.;
+        /// Scale imported OpenCASCADE shapes by this factor.
+        OCCScaling: number = 1.;
+        /// Length unit to convert imported OpenCASCADE shapes to, e.g. `"M"`.
+        OCCTargetUnit: string = "";
+        /// Point color.
+        PointsColor["Color.Points"]: color = (90, 90, 90);
+        /// Curve color.
+        CurvesColor["Color.Curves"]: color = (0, 0, 255);
+        /// Surface color.
+        SurfacesColor["Color.Surfaces"]: color = (128, 128, 128);
+        /// Volume color.
+        VolumesColor["Color.Volumes"]: color = (255, 255, 0);
+    }
+
+    /// Mesh options.
+    Mesh {
+        /// 2D meshing algorithm, see [`MeshAlgorithm2D`](crate::model::MeshAlgorithm2D).
+        Algorithm: number = 6.;
+        /// 3D meshing algorithm: 1 for Delaunay, 4 for Frontal, 7 for MMG3D, 9 for R-tree, 10 for HXT.
+        Algorithm3D: number = 1.;
+        /// Factor applied to all mesh element sizes.
+        CharacteristicLengthFactor: number = 1.;
+        /// Minimum mesh element size.
+        CharacteristicLengthMin: number = 0.;
+        /// Maximum mesh element size.
+        CharacteristicLengthMax: number = 1e22;
+        /// Compute mesh element sizes from the sizes given at points.
+        CharacteristicLengthFromPoints: number = 1.;
+        /// Compute mesh element sizes from the curvature of the geometry.
+        CharacteristicLengthFromCurvature: number = 0.;
+        /// Extend mesh element sizes from the boundary into surfaces and volumes.
+        CharacteristicLengthExtendFromBoundary: number = 1.;
+        /// Maximum number of threads for 1D meshing, 0 for `General.NumThreads`.
+        MaxNumThreads1D: number = 0.;
+        /// Maximum number of threads for 2D meshing, 0 for `General.NumThreads`.
+        MaxNumThreads2D: number = 0.;
+        /// Maximum number of threads for 3D meshing, 0 for `General.NumThreads`.
+        MaxNumThreads3D: number = 0.;
+        /// Element order.
+        ElementOrder: number = 1.;
+        /// Use straight-sided high-order elements.
+        SecondOrderLinear: number = 0.;
+        /// Optimize high-order meshes: 0 for none, 1 for optimization, 2 for elastic+optimization, 3 for elastic, 4 for fast curving.
+        HighOrderOptimize: number = 0.;
+        /// Optimize the quality of tetrahedral meshes.
+        Optimize: number = 1.;
+        /// Optimize the quality of tetrahedral meshes with Netgen.
+        OptimizeNetgen: number = 0.;
+        /// Recombine all triangular meshes into quadrangles.
+        RecombineAll: number = 0.;
+        /// Recombination algorithm, see [`RecombinationAlgorithm`](crate::model::RecombinationAlgorithm).
+        RecombinationAlgorithm: number = 1.;
+        /// Subdivide the mesh: 0 for none, 1 for all quadrangles, 2 for all hexahedra.
+        SubdivisionAlgorithm: number = 0.;
+        /// Number of smoothing steps applied to the final mesh.
+        Smoothing: number = 1.;
+        /// Scale the mesh by this factor when saving it.
+        ScalingFactor: number = 1.;
+        /// Mesh output format, 10 to guess it from the file extension.
+        Format: number = 10.;
+        /// Version of the MSH file format to write.
+        MshFileVersion: number = 4.1;
+        /// Write binary mesh files.
+        Binary: number = 0.;
+        /// Save all elements, even those not in a physical group.
+        SaveAll: number = 0.;
+        /// Node color.
+        NodesColor["Color.Nodes"]: color = (0, 0, 255);
+        /// Line element color.
+        LinesColor["Color.Lines"]: color = (0, 0, 0);
+        /// Triangle color.
+        TrianglesColor["Color.Triangles"]: color = (160, 150, 255);
+        /// Quadrangle color.
+        QuadranglesColor["Color.Quadrangles"]: color = (130, 120, 225);
+        /// Tetrahedron color.
+        TetrahedraColor["Color.Tetrahedra"]: color = (160, 150, 255);
+    }
+
+    /// Solver options.
+    Solver {
+        /// Name of the first solver.
+        Name0: string = "GetDP";
+        /// Executable of the first solver.
+        Executable0: string = "";
+    }
+}
@@ -0,0 +1,336 @@
+//! Typed Gmsh options.
+//!
+//! Every option in the catalogue knows its full name, the type of its value and
+//! its default, so a typo in an option name is a compile error instead of a
+//! runtime `UnknownOption`.
+//! ```no_run
+//! # use rgmsh::{Gmsh, GmshResult};
+//! # use rgmsh::options::{self, Color, GmshOption};
+//! # fn main() -> GmshResult<()> {
+//! let gmsh = Gmsh::initialize()?;
+//! gmsh.set_option(options::Mesh::CharacteristicLengthMax, 0.1)?;
+//! gmsh.set_option(options::General::BackgroundColor, Color::rgb(0, 0, 0))?;
+//!
+//! let version = gmsh.get_option(options::General::Version)?;
+//! let default_order = options::Mesh::ElementOrder.default_value();
+//! # Ok(())
+//! # }
+//! ```
+//! Options missing from the catalogue can still be used through the untyped
+//! [`get_number_option`](crate::Gmsh::get_number_option) family of methods.
+//!
+//! The catalogue follows Gmsh's own option table. `scripts/gen_options.py`
+//! regenerates it from `Common/DefaultOptions.h` in the Gmsh sources.
+
+use crate::{Gmsh, GmshResult};
+
+/// An RGBA color option value.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Color {
+    /// Red component.
+    pub r: u8,
+    /// Green component.
+    pub g: u8,
+    /// Blue component.
+    pub b: u8,
+    /// Alpha component, 255 is opaque.
+    pub a: u8,
+}
+
+impl Color {
+    /// An opaque color.
+    pub fn rgb(r: u8, g: u8, b: u8) -> Color {
+        Color { r, g, b, a: 255 }
+    }
+}
+
+impl From<(u8, u8, u8)> for Color {
+    fn from((r, g, b): (u8, u8, u8)) -> Color {
+        Color::rgb(r, g, b)
+    }
+}
+
+/// The type of an option value: a number, a string or a color.
+pub trait OptionValue: Sized {
+    /// Get the value of the named option.
+    fn get(gmsh: &Gmsh, name: &str) -> GmshResult<Self>;
+
+    /// Set the value of the named option.
+    fn set(gmsh: &Gmsh, name: &str, value: Self) -> GmshResult<()>;
+}
+
+impl OptionValue for f64 {
+    fn get(gmsh: &Gmsh, name: &str) -> GmshResult<f64> {
+        gmsh.get_number_option(name)
+    }
+
+    fn set(gmsh: &Gmsh, name: &str, value: f64) -> GmshResult<()> {
+        gmsh.set_number_option(name, value)
+    }
+}
+
+impl OptionValue for String {
+    fn get(gmsh: &Gmsh, name: &str) -> GmshResult<String> {
+        gmsh.get_string_option(name)
+    }
+
+    fn set(gmsh: &Gmsh, name: &str, value: String) -> GmshResult<()> {
+        gmsh.set_string_option(name, &value)
+    }
+}
+
+impl OptionValue for Color {
+    fn get(gmsh: &Gmsh, name: &str) -> GmshResult<Color> {
+        gmsh.get_color_option(name)
+    }
+
+    fn set(gmsh: &Gmsh, name: &str, value: Color) -> GmshResult<()> {
+        gmsh.set_color_option(name, value)
+    }
+}
+
+/// A Gmsh option from the catalogue.
+pub trait GmshOption: Copy {
+    /// The type of the option value.
+    type Value: OptionValue;
+
+    /// The full option name, e.g. `"Mesh.Algorithm"`.
+    fn name(self) -> &'static str;
+
+    /// The Gmsh default value.
+    fn default_value(self) -> Self::Value;
+}
+
+impl Gmsh {
+    /// Get the value of an option.
+    pub fn get_option<O: GmshOption>(&self, option: O) -> GmshResult<O::Value> {
+        O::Value::get(self, option.name())
+    }
+
+    /// Set the value of an option.
+    pub fn set_option<O, V>(&self, option: O, value: V) -> GmshResult<()>
+    where
+        O: GmshOption,
+        V: Into<O::Value>,
+    {
+        O::Value::set(self, option.name(), value.into())
+    }
+
+    /// Reset an option to its default value.
+    pub fn reset_option<O: GmshOption>(&self, option: O) -> GmshResult<()> {
+        O::Value::set(self, option.name(), option.default_value())
+    }
+}
+
+macro_rules! option_type {
+    (number) => {
+        f64
+    };
+    // full paths, in case an option is named like a type
+    (string) => {
+        ::std::string::String
+    };
+    (color) => {
+        $crate::options::Color
+    };
+}
+
+macro_rules! option_name {
+    ($category: ident, $option: ident) => {
+        concat!(stringify!($category), ".", stringify!($option))
+    };
+    ($category: ident, $option: ident, $suffix: literal) => {
+        concat!(stringify!($category), ".", $suffix)
+    };
+}
+
+// Generate one module per option category, with one unit struct per option.
+// The Gmsh option name is `Category.Option`, unless a suffix is given.
+macro_rules! options {
+    ($(
+        $(#[$category_doc: meta])*
+        $category: ident {
+            $(
+                $(#[$doc: meta])*
+                $option: ident $([$suffix: literal])? : $kind: ident = $default: expr;
+            )*
+        }
+    )*) => {
+        $(
+            $(#[$category_doc])*
+            #[allow(non_snake_case)]
+            pub mod $category {
+                use super::*;
+
+                $(
+                    $(#[$doc])*
+                    #[doc = ""]
+                    #[doc = concat!("`", option_name!($category, $option $(, $suffix)?), "`, default `", stringify!($default), "`.")]
+                    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+                    pub struct $option;
+
+                    impl GmshOption for $option {
+                        type Value = option_type!($kind);
+
+                        fn name(self) -> &'static str {
+                            option_name!($category, $option $(, $suffix)?)
+                        }
+
+                        fn default_value(self) -> Self::Value {
+                            <option_type!($kind)>::from($default)
+                        }
+                    }
+                )*
+            }
+        )*
+    };
+}
+
+include!("option_table.rs");
+
+/// Option overrides that are undone when the guard is dropped.
+///
+/// Options are global to the Gmsh process, so a function that changes an option
+/// changes it for everyone after it. Options set through the guard are
+/// restored to their previous values when it goes out of scope, even during a
+/// panic. The guard dereferences to [`Gmsh`], so it can be used in its place.
+/// ```no_run
+/// # use rgmsh::{Gmsh, GmshResult};
+/// # use rgmsh::options;
+/// # fn main() -> GmshResult<()> {
+/// let mut gmsh = Gmsh::initialize()?;
+/// {
+///     let mut fine = gmsh.scoped_options();
+///     fine.set_option(options::Mesh::CharacteristicLengthMax, 0.01)?;
+///     fine.set_number_option("Mesh.Algorithm", 5.)?;
+///     // ... mesh with the fine settings
+/// }
+/// // the previous sizes and algorithm are back
+/// # Ok(())
+/// # }
+/// ```
+pub struct OptionGuard<'gmsh> {
+    gmsh: &'gmsh mut Gmsh,
+    previous: Vec<Restore>,
+}
+
+// Puts back the saved value of one option
+type Restore = Box<dyn FnOnce(&mut Gmsh)>;
+
+impl<'gmsh> OptionGuard<'gmsh> {
+    // Save the current value of an option, then set the new one
+    fn replace<V: OptionValue + 'static>(&mut self, name: &str, value: V) -> GmshResult<()> {
+        let old = V::get(self.gmsh, name)?;
+        V::set(self.gmsh, name, value)?;
+        let name = name.to_string();
+        self.previous.push(Box::new(move |gmsh: &mut Gmsh| {
+            // don't check restoration errors, the option was valid when saved
+            let _ = V::set(gmsh, &name, old);
+        }));
+        Ok(())
+    }
+
+    /// Set a numeric option until the guard is dropped.
+    pub fn set_number_option(&mut self, name: &str, value: f64) -> GmshResult<()> {
+        self.replace(name, value)
+    }
+
+    /// Set a string option until the guard is dropped.
+    pub fn set_string_option(&mut self, name: &str, value: &str) -> GmshResult<()> {
+        self.replace(name, value.to_string())
+    }
+
+    /// Set a color option until the guard is dropped.
+    pub fn set_color_option(&mut self, name: &str, color: Color) -> GmshResult<()> {
+        self.replace(name, color)
+    }
+
+    /// Set an option until the guard is dropped.
+    pub fn set_option<O, V>(&mut self, option: O, value: V) -> GmshResult<()>
+    where
+        O: GmshOption,
+        O::Value: 'static,
+        V: Into<O::Value>,
+    {
+        self.replace(option.name(), value.into())
+    }
+}
+
+impl<'gmsh> std::ops::Deref for OptionGuard<'gmsh> {
+    type Target = Gmsh;
+
+    fn deref(&self) -> &Gmsh {
+        self.gmsh
+    }
+}
+
+impl<'gmsh> std::ops::DerefMut for OptionGuard<'gmsh> {
+    fn deref_mut(&mut self) -> &mut Gmsh {
+        self.gmsh
+    }
+}
+
+impl<'gmsh> Drop for OptionGuard<'gmsh> {
+    fn drop(&mut self) {
+        // undo in reverse so an option set twice gets its original value back
+        while let Some(restore) = self.previous.pop() {
+            restore(self.gmsh);
+        }
+    }
+}
+
+impl Gmsh {
+    /// Start a set of option overrides, undone when the guard is dropped.
+    pub fn scoped_options(&mut self) -> OptionGuard<'_> {
+        OptionGuard {
+            gmsh: self,
+            previous: Vec::new(),
+        }
+    }
+
+    /// Set a numeric option until the returned guard is dropped.
+    pub fn set_number_option_scoped(
+        &mut self,
+        name: &str,
+        value: f64,
+    ) -> GmshResult<OptionGuard<'_>> {
+        let mut guard = self.scoped_options();
+        guard.set_number_option(name, value)?;
+        Ok(guard)
+    }
+
+    /// Set a string option until the returned guard is dropped.
+    pub fn set_string_option_scoped(
+        &mut self,
+        name: &str,
+        value: &str,
+    ) -> GmshResult<OptionGuard<'_>> {
+        let mut guard = self.scoped_options();
+        guard.set_string_option(name, value)?;
+        Ok(guard)
+    }
+
+    /// Run a function with some numeric options overridden, then restore them.
+    /// ```no_run
+    /// # use rgmsh::{Gmsh, GmshResult};
+    /// # fn main() -> GmshResult<()> {
+    /// let mut gmsh = Gmsh::initialize()?;
+    /// gmsh.with_options(&[("Mesh.Algorithm3D", 10.)], |gmsh| -> GmshResult<()> {
+    ///     let mut geom = gmsh.create_occ_model("model")?;
+    ///     geom.add_box((0., 0., 0.), (1., 1., 1.))?;
+    ///     geom.generate_mesh(3)
+    /// })??;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_options<F, R>(&mut self, overrides: &[(&str, f64)], f: F) -> GmshResult<R>
+    where
+        F: FnOnce(&mut Gmsh) -> R,
+    {
+        let mut guard = self.scoped_options();
+        for &(name, value) in overrides {
+            guard.set_number_option(name, value)?;
+        }
+        Ok(f(&mut guard))
+    }
+}
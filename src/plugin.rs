@@ -0,0 +1,590 @@
+//! Post-processing plugins.
+//!
+//! Plugins transform views, e.g. by cutting them with a plane or evaluating an
+//! expression on them. Each plugin is described by a struct holding its options.
+//! ```no_run
+//! # use rgmsh::{Gmsh, GmshResult};
+//! # use rgmsh::plugin::CutPlane;
+//! # fn main() -> GmshResult<()> {
+//! let gmsh = Gmsh::initialize()?;
+//! let results = gmsh.add_view("results")?;
+//! // ... add data to the view
+//!
+//! // cut the results with the plane z = 0.5
+//! let cut = gmsh.run_plugin(&CutPlane::new(results, (0., 0., 1., -0.5)))?;
+//! if let Some(view) = cut {
+//!     gmsh.write_view(view, "cut.pos")?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::interface::vec_from_raw;
+use crate::model::PhysicalGroupTag;
+use crate::view::ViewTag;
+use crate::{check_main_error, check_model_error, get_cstring, Gmsh, GmshError, GmshResult};
+use std::os::raw::c_int;
+
+/// A plugin option value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PluginValue {
+    /// A numeric option.
+    Number(f64),
+    /// A string option.
+    Str(String),
+    /// A view to work on.
+    View(ViewTag),
+}
+
+/// A Gmsh plugin with its options.
+pub trait Plugin {
+    /// The plugin name, e.g. `"CutPlane"`.
+    fn name(&self) -> &'static str;
+
+    /// The plugin options to set before running it.
+    fn options(&self) -> Vec<(&'static str, PluginValue)>;
+
+    /// Check the options can be passed to Gmsh, before any of them is set.
+    fn check(&self) -> GmshResult<()> {
+        Ok(())
+    }
+}
+
+// Declare a plugin that only takes the view to work on
+macro_rules! view_plugin {
+    ($(#[$doc:meta])* $plugin: ident) => {
+        $(#[$doc])*
+        #[derive(Debug, Copy, Clone, PartialEq)]
+        pub struct $plugin {
+            /// The input view.
+            pub view: ViewTag,
+        }
+
+        impl Plugin for $plugin {
+            fn name(&self) -> &'static str {
+                stringify!($plugin)
+            }
+
+            fn options(&self) -> Vec<(&'static str, PluginValue)> {
+                vec![("View", PluginValue::View(self.view))]
+            }
+        }
+    };
+}
+
+view_plugin!(
+    /// Compute the curl of a vector view.
+    Curl
+);
+view_plugin!(
+    /// Compute the gradient of a view.
+    Gradient
+);
+view_plugin!(
+    /// Compute the divergence of a vector view.
+    Divergence
+);
+
+/// Cut a view with the plane `a * x + b * y + c * z + d = 0`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CutPlane {
+    /// The input view.
+    pub view: ViewTag,
+    /// The plane coefficients `(a, b, c, d)`.
+    pub plane: (f64, f64, f64, f64),
+    /// Keep the volume on one side of the plane instead of the cut: `1` for
+    /// the positive side, `-1` for the negative side and `0` for the cut only.
+    pub extract_volume: i32,
+}
+
+impl CutPlane {
+    /// Cut a view with a plane.
+    pub fn new(view: ViewTag, plane: (f64, f64, f64, f64)) -> CutPlane {
+        CutPlane {
+            view,
+            plane,
+            extract_volume: 0,
+        }
+    }
+}
+
+impl Plugin for CutPlane {
+    fn name(&self) -> &'static str {
+        "CutPlane"
+    }
+
+    fn options(&self) -> Vec<(&'static str, PluginValue)> {
+        let (a, b, c, d) = self.plane;
+        vec![
+            ("A", PluginValue::Number(a)),
+            ("B", PluginValue::Number(b)),
+            ("C", PluginValue::Number(c)),
+            ("D", PluginValue::Number(d)),
+            (
+                "ExtractVolume",
+                PluginValue::Number(self.extract_volume.into()),
+            ),
+            ("View", PluginValue::View(self.view)),
+        ]
+    }
+}
+
+/// Cut a view with a sphere.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CutSphere {
+    /// The input view.
+    pub view: ViewTag,
+    /// The sphere center.
+    pub center: (f64, f64, f64),
+    /// The sphere radius.
+    pub radius: f64,
+    /// Keep the volume inside (`-1`) or outside (`1`) the sphere instead of
+    /// the cut, or `0` for the cut only.
+    pub extract_volume: i32,
+}
+
+impl CutSphere {
+    /// Cut a view with a sphere.
+    pub fn new(view: ViewTag, center: (f64, f64, f64), radius: f64) -> CutSphere {
+        CutSphere {
+            view,
+            center,
+            radius,
+            extract_volume: 0,
+        }
+    }
+}
+
+impl Plugin for CutSphere {
+    fn name(&self) -> &'static str {
+        "CutSphere"
+    }
+
+    fn options(&self) -> Vec<(&'static str, PluginValue)> {
+        vec![
+            ("Xc", PluginValue::Number(self.center.0)),
+            ("Yc", PluginValue::Number(self.center.1)),
+            ("Zc", PluginValue::Number(self.center.2)),
+            ("R", PluginValue::Number(self.radius)),
+            (
+                "ExtractVolume",
+                PluginValue::Number(self.extract_volume.into()),
+            ),
+            ("View", PluginValue::View(self.view)),
+        ]
+    }
+}
+
+/// Extract the isosurface of a view at a value.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Isosurface {
+    /// The input view.
+    pub view: ViewTag,
+    /// The isosurface value.
+    pub value: f64,
+    /// Keep the volume above (`1`) or below (`-1`) the value instead of the
+    /// isosurface, or `0` for the isosurface only.
+    pub extract_volume: i32,
+}
+
+impl Isosurface {
+    /// Extract the isosurface of a view.
+    pub fn new(view: ViewTag, value: f64) -> Isosurface {
+        Isosurface {
+            view,
+            value,
+            extract_volume: 0,
+        }
+    }
+}
+
+impl Plugin for Isosurface {
+    fn name(&self) -> &'static str {
+        "Isosurface"
+    }
+
+    fn options(&self) -> Vec<(&'static str, PluginValue)> {
+        vec![
+            ("Value", PluginValue::Number(self.value)),
+            (
+                "ExtractVolume",
+                PluginValue::Number(self.extract_volume.into()),
+            ),
+            ("View", PluginValue::View(self.view)),
+        ]
+    }
+}
+
+/// Integrate a view over its elements.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Integrate {
+    /// The input view.
+    pub view: ViewTag,
+    /// Only integrate over elements of this dimension, or all of them for `-1`.
+    pub dim: i32,
+}
+
+impl Plugin for Integrate {
+    fn name(&self) -> &'static str {
+        "Integrate"
+    }
+
+    fn options(&self) -> Vec<(&'static str, PluginValue)> {
+        vec![
+            ("Dimension", PluginValue::Number(self.dim.into())),
+            ("View", PluginValue::View(self.view)),
+        ]
+    }
+}
+
+/// Evaluate expressions on a view, one per output component.
+///
+/// Expressions can use the view values `v0, v1, ...`, the values of another
+/// view `w0, w1, ...`, the coordinates `x, y, z` and the time `Time`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MathEval {
+    /// The input view.
+    pub view: ViewTag,
+    /// Another view available to the expressions.
+    pub other_view: Option<ViewTag>,
+    /// The expressions, at most 9. Running the plugin with more fails with
+    /// `ModelBadInput`.
+    pub expressions: Vec<String>,
+}
+
+impl Plugin for MathEval {
+    fn name(&self) -> &'static str {
+        "MathEval"
+    }
+
+    fn options(&self) -> Vec<(&'static str, PluginValue)> {
+        const EXPRESSIONS: [&str; 9] = [
+            "Expression0",
+            "Expression1",
+            "Expression2",
+            "Expression3",
+            "Expression4",
+            "Expression5",
+            "Expression6",
+            "Expression7",
+            "Expression8",
+        ];
+        let mut options: Vec<_> = EXPRESSIONS
+            .iter()
+            .zip(self.expressions.iter())
+            .map(|(&name, expr)| (name, PluginValue::Str(expr.clone())))
+            .collect();
+        // clear the expressions that aren't used
+        for &name in EXPRESSIONS.iter().skip(self.expressions.len()) {
+            options.push((name, PluginValue::Str(String::new())));
+        }
+        // plugin options persist between runs, so always reset the other view
+        match self.other_view {
+            Some(other) => options.push(("OtherView", PluginValue::View(other))),
+            None => options.push(("OtherView", PluginValue::Number(-1.))),
+        }
+        options.push(("View", PluginValue::View(self.view)));
+        options
+    }
+
+    fn check(&self) -> GmshResult<()> {
+        if self.expressions.len() > 9 {
+            return Err(GmshError::ModelBadInput);
+        }
+        Ok(())
+    }
+}
+
+/// Extract the boundary of a view.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Skin {
+    /// The input view.
+    pub view: ViewTag,
+    /// Only use the visible elements.
+    pub visible: bool,
+}
+
+impl Plugin for Skin {
+    fn name(&self) -> &'static str {
+        "Skin"
+    }
+
+    fn options(&self) -> Vec<(&'static str, PluginValue)> {
+        vec![
+            (
+                "Visible",
+                PluginValue::Number(if self.visible { 1. } else { 0. }),
+            ),
+            ("View", PluginValue::View(self.view)),
+        ]
+    }
+}
+
+/// Interpolate a view at a point.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Probe {
+    /// The input view.
+    pub view: ViewTag,
+    /// Where to interpolate.
+    pub point: (f64, f64, f64),
+}
+
+impl Plugin for Probe {
+    fn name(&self) -> &'static str {
+        "Probe"
+    }
+
+    fn options(&self) -> Vec<(&'static str, PluginValue)> {
+        vec![
+            ("X", PluginValue::Number(self.point.0)),
+            ("Y", PluginValue::Number(self.point.1)),
+            ("Z", PluginValue::Number(self.point.2)),
+            ("View", PluginValue::View(self.view)),
+        ]
+    }
+}
+
+/// Compute the volume of the mesh of a physical group.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct MeshVolume {
+    /// The physical group to measure, or `None` for the whole mesh.
+    pub physical_group: Option<PhysicalGroupTag>,
+    /// The dimension of the elements to measure without a physical group, or
+    /// `-1` for all of them.
+    pub dim: i32,
+}
+
+impl Plugin for MeshVolume {
+    fn name(&self) -> &'static str {
+        "MeshVolume"
+    }
+
+    fn options(&self) -> Vec<(&'static str, PluginValue)> {
+        let (physical, dim) = match self.physical_group {
+            Some(group) => (group.to_raw(), group.dim()),
+            None => (-1, self.dim),
+        };
+        vec![
+            ("Physical", PluginValue::Number(physical.into())),
+            ("Dimension", PluginValue::Number(dim.into())),
+        ]
+    }
+}
+
+/// Crack the mesh along a physical group by duplicating its nodes.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Crack {
+    /// The physical group of the crack.
+    pub physical_group: PhysicalGroupTag,
+    /// The physical group of the crack's open boundary, if any.
+    pub open_boundary: Option<PhysicalGroupTag>,
+    /// Which side of the crack gets the new nodes.
+    pub normal: (f64, f64, f64),
+}
+
+impl Plugin for Crack {
+    fn name(&self) -> &'static str {
+        "Crack"
+    }
+
+    fn options(&self) -> Vec<(&'static str, PluginValue)> {
+        // 0 is no open boundary
+        let open_boundary = self.open_boundary.map_or(0, PhysicalGroupTag::to_raw);
+        vec![
+            (
+                "Dimension",
+                PluginValue::Number(self.physical_group.dim().into()),
+            ),
+            (
+                "PhysicalGroup",
+                PluginValue::Number(self.physical_group.to_raw().into()),
+            ),
+            (
+                "OpenBoundaryPhysicalGroup",
+                PluginValue::Number(open_boundary.into()),
+            ),
+            ("NormalX", PluginValue::Number(self.normal.0)),
+            ("NormalY", PluginValue::Number(self.normal.1)),
+            ("NormalZ", PluginValue::Number(self.normal.2)),
+        ]
+    }
+}
+
+/// Compute (co)homology bases of physical groups on the mesh.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HomologyComputation {
+    /// The domain physical groups, empty for the whole mesh.
+    pub domain: Vec<PhysicalGroupTag>,
+    /// The subdomain physical groups, empty for none.
+    pub subdomain: Vec<PhysicalGroupTag>,
+    /// The dimensions of the chains to save.
+    pub dims: Vec<i32>,
+    /// Compute homology bases.
+    pub compute_homology: bool,
+    /// Compute cohomology bases.
+    pub compute_cohomology: bool,
+    /// Write the chains to this mesh file.
+    pub file_name: String,
+}
+
+impl Plugin for HomologyComputation {
+    fn name(&self) -> &'static str {
+        "HomologyComputation"
+    }
+
+    fn options(&self) -> Vec<(&'static str, PluginValue)> {
+        // lists are given as comma-separated strings
+        fn list(values: &[i32]) -> PluginValue {
+            let strings: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+            PluginValue::Str(strings.join(", "))
+        }
+        let tags = |groups: &[PhysicalGroupTag]| -> Vec<i32> {
+            groups.iter().map(|g| g.to_raw()).collect()
+        };
+        let flag = |on: bool| PluginValue::Number(if on { 1. } else { 0. });
+        vec![
+            ("DomainPhysicalGroups", list(&tags(&self.domain))),
+            ("SubdomainPhysicalGroups", list(&tags(&self.subdomain))),
+            ("DimensionOfChainsToSave", list(&self.dims)),
+            ("ComputeHomology", flag(self.compute_homology)),
+            ("ComputeCohomology", flag(self.compute_cohomology)),
+            ("Filename", PluginValue::Str(self.file_name.clone())),
+        ]
+    }
+}
+
+/// Compute element quality measures, making one `ElementData` view per
+/// enabled measure.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AnalyseMeshQuality {
+    /// Compute the ratio of the minimum to maximum Jacobian determinant.
+    pub jacobian_determinant: bool,
+    /// Compute the minimum signed inverse gradient error (SIGE).
+    pub ige_measure: bool,
+    /// Compute the minimum signed inverse condition number (SICN).
+    pub icn_measure: bool,
+    /// The dimension of the elements to analyse, or `-1` for the highest.
+    pub dim: i32,
+}
+
+impl Plugin for AnalyseMeshQuality {
+    fn name(&self) -> &'static str {
+        "AnalyseMeshQuality"
+    }
+
+    fn options(&self) -> Vec<(&'static str, PluginValue)> {
+        let flag = |on: bool| PluginValue::Number(if on { 1. } else { 0. });
+        vec![
+            ("JacobianDeterminant", flag(self.jacobian_determinant)),
+            ("IGEMeasure", flag(self.ige_measure)),
+            ("ICNMeasure", flag(self.icn_measure)),
+            // don't hide any elements
+            ("HidingThreshold", PluginValue::Number(99.)),
+            ("ThresholdGreater", flag(true)),
+            ("CreateView", flag(true)),
+            // the plugin caches results, which go stale when the mesh changes
+            ("Recompute", flag(true)),
+            ("DimensionOfElements", PluginValue::Number(self.dim.into())),
+        ]
+    }
+}
+
+impl Gmsh {
+    /// Get the tags of all views.
+    pub fn get_view_tags(&self) -> GmshResult<Vec<ViewTag>> {
+        unsafe {
+            let mut ierr: c_int = 0;
+            let mut tags: *mut c_int = std::ptr::null_mut();
+            let mut tags_n: usize = 0;
+            gmsh_sys::gmshViewGetTags(&mut tags, &mut tags_n, &mut ierr);
+            let tags = vec_from_raw(tags, tags_n);
+            check_model_error!(ierr, tags.into_iter().map(ViewTag).collect())
+        }
+    }
+
+    /// Run a plugin. Returns the view it made, if any.
+    ///
+    /// Fails with `ModelBadInput` if the plugin options are invalid.
+    pub fn run_plugin<P: Plugin>(&self, plugin: &P) -> GmshResult<Option<ViewTag>> {
+        plugin.check()?;
+        let name = plugin.name();
+        let c_name = get_cstring(name)?;
+        for (option, value) in plugin.options() {
+            let c_option = get_cstring(option)?;
+            let mut ierr: c_int = 0;
+            match value {
+                PluginValue::Number(number) => unsafe {
+                    gmsh_sys::gmshPluginSetNumber(
+                        c_name.as_ptr(),
+                        c_option.as_ptr(),
+                        number,
+                        &mut ierr,
+                    );
+                },
+                PluginValue::Str(string) => unsafe {
+                    let c_string = get_cstring(&string)?;
+                    gmsh_sys::gmshPluginSetString(
+                        c_name.as_ptr(),
+                        c_option.as_ptr(),
+                        c_string.as_ptr(),
+                        &mut ierr,
+                    );
+                },
+                // plugins refer to views by index, not by tag
+                PluginValue::View(view) => unsafe {
+                    let index = gmsh_sys::gmshViewGetIndex(view.0, &mut ierr);
+                    check_model_error!(ierr, ())?;
+                    gmsh_sys::gmshPluginSetNumber(
+                        c_name.as_ptr(),
+                        c_option.as_ptr(),
+                        index.into(),
+                        &mut ierr,
+                    );
+                },
+            }
+            check_main_error!(ierr, ())?;
+        }
+
+        // find the new view by comparing the views before and after
+        let before = self.get_view_tags()?;
+        unsafe {
+            let mut ierr: c_int = 0;
+            gmsh_sys::gmshPluginRun(c_name.as_ptr(), &mut ierr);
+            check_main_error!(ierr, ())?;
+        }
+        let after = self.get_view_tags()?;
+        Ok(after.into_iter().find(|view| !before.contains(view)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn math_eval_resets_other_view() {
+        let plugin = MathEval {
+            view: ViewTag(1),
+            other_view: None,
+            expressions: vec!["v0 * 2".to_string()],
+        };
+        let options = plugin.options();
+        assert!(options.contains(&("OtherView", PluginValue::Number(-1.))));
+        assert!(options.contains(&("Expression1", PluginValue::Str(String::new()))));
+    }
+
+    #[test]
+    fn math_eval_takes_nine_expressions() {
+        let mut plugin = MathEval {
+            view: ViewTag(1),
+            other_view: None,
+            expressions: vec!["v0".to_string(); 9],
+        };
+        assert!(plugin.check().is_ok());
+        plugin.expressions.push("v0".to_string());
+        match plugin.check() {
+            Err(GmshError::ModelBadInput) => (),
+            _ => panic!(),
+        }
+    }
+}
@@ -0,0 +1,424 @@
+//! Run Gmsh in a child process.
+//!
+//! Gmsh can crash on bad input, e.g. broken CAD files, and a crash takes the
+//! whole process with it. [`RemoteGmsh`] drives Gmsh in an `rgmsh-server`
+//! child process instead, so a crash only ends the child and shows up as a
+//! `Disconnected` error.
+//!
+//! Remote models mirror the [`GeoModel`](crate::GeoModel) and
+//! [`OccModel`](crate::OccModel) APIs for building geometry and meshing it.
+//! Meshes are sent back whole as an [`ExtractedMesh`].
+//! ```no_run
+//! # use rgmsh::GmshResult;
+//! use rgmsh::remote::RemoteGmsh;
+//!
+//! # fn main() -> GmshResult<()> {
+//! let gmsh = RemoteGmsh::spawn()?;
+//! let mut geom = gmsh.create_occ_model("box")?;
+//! geom.add_box((0., 0., 0.), (1., 1., 1.))?;
+//! geom.generate_mesh(3)?;
+//!
+//! let mesh = geom.get_mesh(3)?;
+//! println!("{} nodes", mesh.nodes.tags.len());
+//! # Ok(())
+//! # }
+//! ```
+//! The server binary is found through the `RGMSH_SERVER` environment variable,
+//! next to the current executable, or on the `PATH`, in that order.
+
+pub mod pool;
+mod protocol;
+pub mod server;
+
+pub use pool::MeshPool;
+
+use crate::io::path_str;
+use crate::model::{
+    BasicShape, CurveTag, MeshElements, MeshNodes, PointTag, SurfaceTag, VolumeTag, WireTag,
+};
+use crate::{GmshError, GmshResult};
+use protocol::{Line, Tokens};
+use std::cell::RefCell;
+use std::ffi::OsStr;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::sync::{Arc, Mutex};
+
+/// A whole mesh, copied out of Gmsh.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtractedMesh {
+    /// All mesh nodes.
+    pub nodes: MeshNodes,
+    /// The mesh elements, one entry per element type.
+    pub elements: Vec<MeshElements>,
+}
+
+// The pipes to a running server
+struct Connection {
+    // shared with kill switches
+    child: Arc<Mutex<Child>>,
+    input: ChildStdin,
+    output: BufReader<ChildStdout>,
+    alive: bool,
+}
+
+impl Connection {
+    // Send a request and wait for the response line
+    fn request(&mut self, request: &str) -> GmshResult<String> {
+        if !self.alive {
+            return Err(GmshError::Disconnected);
+        }
+        let mut response = String::new();
+        let sent = writeln!(self.input, "{}", request).and_then(|_| self.input.flush());
+        match sent.and_then(|_| self.output.read_line(&mut response)) {
+            Ok(n) if n > 0 => Ok(response),
+            // the server crashed or quit
+            _ => {
+                self.alive = false;
+                if let Ok(mut child) = self.child.lock() {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                }
+                Err(GmshError::Disconnected)
+            }
+        }
+    }
+}
+
+// Kills a server, ending any request waiting on it with `Disconnected`
+#[derive(Clone)]
+pub(crate) struct KillSwitch(Arc<Mutex<Child>>);
+
+impl KillSwitch {
+    pub(crate) fn kill(&self) {
+        if let Ok(mut child) = self.0.lock() {
+            let _ = child.kill();
+        }
+    }
+}
+
+/// A Gmsh context running in a child process.
+pub struct RemoteGmsh {
+    connection: RefCell<Connection>,
+}
+
+impl RemoteGmsh {
+    /// Start an `rgmsh-server` process and initialize Gmsh in it.
+    pub fn spawn() -> GmshResult<RemoteGmsh> {
+        RemoteGmsh::spawn_server(server_path())
+    }
+
+    /// Start a server from a given executable.
+    pub fn spawn_server<P: AsRef<OsStr>>(program: P) -> GmshResult<RemoteGmsh> {
+        let mut child = Command::new(program)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|_| GmshError::Initialization)?;
+        let (input, output) = match (child.stdin.take(), child.stdout.take()) {
+            (Some(input), Some(output)) => (input, BufReader::new(output)),
+            _ => return Err(GmshError::Initialization),
+        };
+        let mut connection = Connection {
+            child: Arc::new(Mutex::new(child)),
+            input,
+            output,
+            alive: true,
+        };
+
+        // the server says whether Gmsh started before taking requests
+        let mut hello = String::new();
+        match connection.output.read_line(&mut hello) {
+            Ok(n) if n > 0 => {
+                Tokens::response(&hello)?;
+            }
+            _ => return Err(GmshError::Initialization),
+        }
+        Ok(RemoteGmsh {
+            connection: RefCell::new(connection),
+        })
+    }
+
+    /// Check if the server is still running.
+    pub fn is_alive(&self) -> bool {
+        self.connection.borrow().alive
+    }
+
+    // Something that can stop the server from another thread
+    pub(crate) fn kill_switch(&self) -> KillSwitch {
+        KillSwitch(self.connection.borrow().child.clone())
+    }
+
+    /// Remove all models from the server.
+    pub fn clear(&mut self) -> GmshResult<()> {
+        self.call_ok(Line::new("clear"))
+    }
+
+    // Send a request, returning the raw response
+    fn call(&self, request: Line) -> GmshResult<String> {
+        self.connection.borrow_mut().request(&request.finish())
+    }
+
+    // Send a request without results
+    fn call_ok(&self, request: Line) -> GmshResult<()> {
+        Tokens::response(&self.call(request)?).map(|_| ())
+    }
+
+    /// Make a new model using the built-in Gmsh geometry kernel.
+    pub fn create_native_model(&self, name: &str) -> GmshResult<RemoteGeoModel<'_>> {
+        self.call_ok(Line::new("new_model").str("geo").str(name))?;
+        Ok(RemoteGeoModel {
+            remote: self,
+            name: name.to_string(),
+        })
+    }
+
+    /// Make a new model using the OpenCASCADE geometry kernel.
+    pub fn create_occ_model(&self, name: &str) -> GmshResult<RemoteOccModel<'_>> {
+        self.call_ok(Line::new("new_model").str("occ").str(name))?;
+        Ok(RemoteOccModel {
+            remote: self,
+            name: name.to_string(),
+        })
+    }
+
+    /// Open a file on the server. Models opened from STEP, IGES and BRep
+    /// files use the OpenCASCADE kernel for further changes, and all others
+    /// the built-in kernel.
+    pub fn open<P: AsRef<Path>>(&self, path: P) -> GmshResult<Vec<RemoteModel<'_>>> {
+        let request = Line::new("open").str(path_str(path.as_ref())?);
+        let response = self.call(request)?;
+        let mut tokens = Tokens::response(&response)?;
+        let num_models: usize = tokens.num()?;
+        (0..num_models)
+            .map(|_| {
+                let name = tokens.str()?;
+                match tokens.str()?.as_str() {
+                    "geo" => Ok(RemoteModel::Geo(RemoteGeoModel { remote: self, name })),
+                    "occ" => Ok(RemoteModel::Occ(RemoteOccModel { remote: self, name })),
+                    _ => Err(GmshError::CInterface),
+                }
+            })
+            .collect()
+    }
+
+    /// Get a numeric option.
+    pub fn get_number_option(&self, name: &str) -> GmshResult<f64> {
+        let response = self.call(Line::new("get_number_option").str(name))?;
+        Tokens::response(&response)?.num()
+    }
+
+    /// Set a numeric option.
+    pub fn set_number_option(&mut self, name: &str, value: f64) -> GmshResult<()> {
+        self.call_ok(Line::new("set_number_option").str(name).num(value))
+    }
+
+    /// Set a string option.
+    pub fn set_string_option(&mut self, name: &str, value: &str) -> GmshResult<()> {
+        self.call_ok(Line::new("set_string_option").str(name).str(value))
+    }
+}
+
+impl Drop for RemoteGmsh {
+    fn drop(&mut self) {
+        let connection = self.connection.get_mut();
+        if connection.alive {
+            // let the server finalize Gmsh, then reap it
+            let _ = writeln!(connection.input, "quit").and_then(|_| connection.input.flush());
+            if let Ok(mut child) = connection.child.lock() {
+                let _ = child.wait();
+            }
+        }
+    }
+}
+
+// Where to find the server executable
+pub(crate) fn server_path() -> PathBuf {
+    let name = format!("rgmsh-server{}", std::env::consts::EXE_SUFFIX);
+    if let Some(path) = std::env::var_os("RGMSH_SERVER") {
+        return PathBuf::from(path);
+    }
+    if let Ok(exe) = std::env::current_exe() {
+        // test and example executables live one directory further down
+        for dir in exe.ancestors().skip(1).take(2) {
+            let candidate = dir.join(&name);
+            if candidate.is_file() {
+                return candidate;
+            }
+        }
+    }
+    PathBuf::from(name)
+}
+
+/// A built-in kernel model in a [`RemoteGmsh`] server.
+pub struct RemoteGeoModel<'remote> {
+    remote: &'remote RemoteGmsh,
+    name: String,
+}
+
+/// An OpenCASCADE kernel model in a [`RemoteGmsh`] server.
+pub struct RemoteOccModel<'remote> {
+    remote: &'remote RemoteGmsh,
+    name: String,
+}
+
+/// A model opened in a [`RemoteGmsh`] server, with the kernel it was opened with.
+pub enum RemoteModel<'remote> {
+    /// A built-in kernel model.
+    Geo(RemoteGeoModel<'remote>),
+    /// An OpenCASCADE kernel model.
+    Occ(RemoteOccModel<'remote>),
+}
+
+impl<'remote> RemoteModel<'remote> {
+    /// The model name.
+    pub fn name(&self) -> &str {
+        match self {
+            RemoteModel::Geo(model) => model.name(),
+            RemoteModel::Occ(model) => model.name(),
+        }
+    }
+}
+
+macro_rules! impl_remote_model {
+    ($model_type: ident) => {
+        impl<'remote> $model_type<'remote> {
+            /// The model name.
+            pub fn name(&self) -> &str {
+                &self.name
+            }
+
+            // Start a request about this model
+            fn request(&self, command: &str) -> Line {
+                Line::new(command).str(&self.name)
+            }
+
+            // Send a request returning a single tag
+            fn call_tag(&self, request: Line) -> GmshResult<i32> {
+                Tokens::response(&self.remote.call(request)?)?.num()
+            }
+
+            /// Remove the model from the server.
+            pub fn remove(self) -> GmshResult<()> {
+                self.remote
+                    .call_ok(Line::new("remove_model").str(&self.name))
+            }
+
+            /// Add a point to the model by specifying its coordinates.
+            pub fn add_point(&mut self, x: f64, y: f64, z: f64) -> GmshResult<PointTag> {
+                let request = self.request("add_point").num(x).num(y).num(z);
+                self.call_tag(request).map(PointTag)
+            }
+
+            /// Add a point to the model and specify a target mesh size `lc` there.
+            pub fn add_point_with_lc(
+                &mut self,
+                x: f64,
+                y: f64,
+                z: f64,
+                lc: f64,
+            ) -> GmshResult<PointTag> {
+                let request = self
+                    .request("add_point_with_lc")
+                    .num(x)
+                    .num(y)
+                    .num(z)
+                    .num(lc);
+                self.call_tag(request).map(PointTag)
+            }
+
+            /// Add a straight line between two points.
+            pub fn add_line(&mut self, p1: PointTag, p2: PointTag) -> GmshResult<CurveTag> {
+                let request = self.request("add_line").num(p1.0).num(p2.0);
+                self.call_tag(request).map(CurveTag)
+            }
+
+            /// Add a curve loop from a closed set of curves.
+            pub fn add_curve_loop(&mut self, curves: &[CurveTag]) -> GmshResult<WireTag> {
+                let raw: Vec<i32> = curves.iter().map(|c| c.0).collect();
+                let request = self.request("add_curve_loop").list(&raw);
+                self.call_tag(request).map(WireTag)
+            }
+
+            /// Add a surface from a WireTag of a closed curve set.
+            pub fn add_plane_surface(&mut self, boundary: WireTag) -> GmshResult<SurfaceTag> {
+                self.add_plane_surface_with_holes(boundary, &[])
+            }
+
+            /// Add a surface with holes.
+            pub fn add_plane_surface_with_holes(
+                &mut self,
+                boundary: WireTag,
+                holes: &[WireTag],
+            ) -> GmshResult<SurfaceTag> {
+                let raw: Vec<i32> = holes.iter().map(|w| w.0).collect();
+                let request = self.request("add_plane_surface").num(boundary.0).list(&raw);
+                self.call_tag(request).map(SurfaceTag)
+            }
+
+            /// Synchronize the underlying CAD representation.
+            pub fn synchronize(&mut self) -> GmshResult<()> {
+                self.remote.call_ok(self.request("synchronize"))
+            }
+
+            /// Mesh the model.
+            pub fn generate_mesh(&mut self, dim: i32) -> GmshResult<()> {
+                self.remote.call_ok(self.request("generate_mesh").num(dim))
+            }
+
+            /// Copy the mesh out of the server: all nodes, and the elements of
+            /// dimension `dim`, or of all dimensions for `-1`.
+            pub fn get_mesh(&self, dim: i32) -> GmshResult<ExtractedMesh> {
+                let response = self.remote.call(self.request("get_mesh").num(dim))?;
+                Tokens::response(&response)?.mesh()
+            }
+        }
+    };
+}
+
+impl_remote_model!(RemoteGeoModel);
+impl_remote_model!(RemoteOccModel);
+
+/// All angle values are in radians, commonly given as fractions of π.
+impl<'remote> RemoteOccModel<'remote> {
+    /// Add a box with a starting point and side lengths from that point.
+    pub fn add_box(
+        &mut self,
+        start_point: (f64, f64, f64),
+        extents: (f64, f64, f64),
+    ) -> GmshResult<VolumeTag> {
+        let request = self
+            .request("add_box")
+            .num(start_point.0)
+            .num(start_point.1)
+            .num(start_point.2)
+            .num(extents.0)
+            .num(extents.1)
+            .num(extents.2);
+        self.call_tag(request).map(VolumeTag)
+    }
+
+    /// Add a sphere with a centroid and radius.
+    pub fn add_sphere(&mut self, centroid: (f64, f64, f64), radius: f64) -> GmshResult<VolumeTag> {
+        let request = self
+            .request("add_sphere")
+            .num(centroid.0)
+            .num(centroid.1)
+            .num(centroid.2)
+            .num(radius);
+        self.call_tag(request).map(VolumeTag)
+    }
+
+    /// Import the shapes of a CAD file on the server, and return the top-level
+    /// entities.
+    pub fn import_shapes<P: AsRef<Path>>(&mut self, path: P) -> GmshResult<Vec<BasicShape>> {
+        let request = self.request("import_shapes").str(path_str(path.as_ref())?);
+        let response = self.remote.call(request)?;
+        let dim_tags: Vec<i32> = Tokens::response(&response)?.list()?;
+        dim_tags
+            .chunks_exact(2)
+            .map(|dt| BasicShape::from_dim_tag(dt[0], dt[1]))
+            .collect()
+    }
+}
@@ -0,0 +1,287 @@
+//! Mesh many independent models in parallel, one Gmsh process per worker.
+//!
+//! Gmsh state is global to a process, so models can't be meshed in parallel
+//! within one process. A [`MeshPool`] runs each worker's jobs on its own
+//! [`RemoteGmsh`] server instead.
+//! ```no_run
+//! # use rgmsh::GmshResult;
+//! use rgmsh::remote::MeshPool;
+//! use std::time::Duration;
+//!
+//! # fn main() -> GmshResult<()> {
+//! let radii: Vec<f64> = (1..=100).map(|i| i as f64 * 0.01).collect();
+//! let pool = MeshPool::new(8).timeout(Duration::from_secs(60)).retries(1);
+//!
+//! let meshes = pool.run(&radii, |gmsh, &radius| {
+//!     let mut geom = gmsh.create_occ_model("variant")?;
+//!     geom.add_sphere((0., 0., 0.), radius)?;
+//!     geom.generate_mesh(3)?;
+//!     geom.get_mesh(3)
+//! });
+//! for (radius, mesh) in radii.iter().zip(&meshes) {
+//!     match mesh {
+//!         Ok(mesh) => println!("r = {}: {} nodes", radius, mesh.nodes.tags.len()),
+//!         Err(e) => println!("r = {}: {}", radius, e),
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use super::protocol::Line;
+use super::{server_path, RemoteGmsh};
+use crate::{GmshError, GmshResult};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// A pool of Gmsh server processes for independent jobs.
+#[derive(Debug, Clone)]
+pub struct MeshPool {
+    workers: usize,
+    timeout: Option<Duration>,
+    retries: u32,
+    server: PathBuf,
+}
+
+impl MeshPool {
+    /// A pool of `workers` server processes, with no time limit and no retries.
+    pub fn new(workers: usize) -> MeshPool {
+        MeshPool {
+            workers: workers.max(1),
+            timeout: None,
+            retries: 0,
+            server: server_path(),
+        }
+    }
+
+    /// Stop jobs that take longer than `timeout`, failing them with `Timeout`.
+    pub fn timeout(mut self, timeout: Duration) -> MeshPool {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Run a job again, on a new server, if its server crashes or times out.
+    ///
+    /// Jobs that fail with a Gmsh error aren't retried, they would fail again.
+    pub fn retries(mut self, retries: u32) -> MeshPool {
+        self.retries = retries;
+        self
+    }
+
+    /// Use a given server executable.
+    pub fn server<P: Into<PathBuf>>(mut self, program: P) -> MeshPool {
+        self.server = program.into();
+        self
+    }
+
+    /// Run `job` on every input and collect the results, in input order.
+    ///
+    /// Each job gets a server with no models in it.
+    pub fn run<I, F, T>(&self, inputs: &[I], job: F) -> Vec<GmshResult<T>>
+    where
+        I: Sync,
+        F: Fn(&RemoteGmsh, &I) -> GmshResult<T> + Sync,
+        T: Send,
+    {
+        let next = AtomicUsize::new(0);
+        let (result_sender, result_receiver) = mpsc::channel();
+        let workers = self.workers.min(inputs.len());
+
+        thread::scope(|scope| {
+            for _ in 0..workers {
+                let result_sender = result_sender.clone();
+                let (next, job) = (&next, &job);
+                scope.spawn(move || {
+                    let mut remote = None;
+                    loop {
+                        let index = next.fetch_add(1, Ordering::SeqCst);
+                        if index >= inputs.len() {
+                            break;
+                        }
+                        let result = self.run_job(&mut remote, |gmsh| job(gmsh, &inputs[index]));
+                        let _ = result_sender.send((index, result));
+                    }
+                });
+            }
+        });
+        drop(result_sender);
+
+        let mut results: Vec<Option<GmshResult<T>>> = inputs.iter().map(|_| None).collect();
+        for (index, result) in result_receiver {
+            results[index] = Some(result);
+        }
+        results
+            .into_iter()
+            .map(|result| result.unwrap_or(Err(GmshError::Disconnected)))
+            .collect()
+    }
+
+    // Run one job with retries, replacing the worker's server when it dies
+    fn run_job<T, F>(&self, remote: &mut Option<RemoteGmsh>, job: F) -> GmshResult<T>
+    where
+        F: Fn(&RemoteGmsh) -> GmshResult<T>,
+    {
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let result = self.attempt(remote, &job);
+            match result {
+                Err(GmshError::Disconnected) | Err(GmshError::Timeout) => {
+                    // start over with a new server
+                    *remote = None;
+                    if attempts > self.retries {
+                        return result;
+                    }
+                }
+                _ => return result,
+            }
+        }
+    }
+
+    fn attempt<T, F>(&self, remote: &mut Option<RemoteGmsh>, job: &F) -> GmshResult<T>
+    where
+        F: Fn(&RemoteGmsh) -> GmshResult<T>,
+    {
+        if remote.is_none() {
+            *remote = Some(RemoteGmsh::spawn_server(&self.server)?);
+        }
+        let gmsh = remote.as_ref().ok_or(GmshError::Disconnected)?;
+        // start from a server without models
+        let job = |gmsh: &RemoteGmsh| {
+            gmsh.call_ok(Line::new("clear"))?;
+            job(gmsh)
+        };
+
+        let timeout = match self.timeout {
+            Some(timeout) => timeout,
+            None => return job(gmsh),
+        };
+        // kill the server if the job runs too long
+        let (done_sender, done_receiver) = mpsc::channel::<()>();
+        let timed_out = Arc::new(AtomicBool::new(false));
+        let watchdog = {
+            let kill_switch = gmsh.kill_switch();
+            let timed_out = timed_out.clone();
+            thread::spawn(move || {
+                if let Err(mpsc::RecvTimeoutError::Timeout) = done_receiver.recv_timeout(timeout) {
+                    timed_out.store(true, Ordering::SeqCst);
+                    kill_switch.kill();
+                }
+            })
+        };
+        let result = job(gmsh);
+        drop(done_sender);
+        let _ = watchdog.join();
+
+        if !timed_out.load(Ordering::SeqCst) {
+            return result;
+        }
+        // the server was killed, even if the job just made it
+        *remote = None;
+        match result {
+            Err(GmshError::Disconnected) => Err(GmshError::Timeout),
+            result => result,
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::Path;
+
+    // Write a shell script standing in for the server
+    fn stub_server(name: &str, script: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rgmsh-pool-{}-{}", std::process::id(), name));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("server");
+        let script = script.replace("$DIR", &dir.to_string_lossy());
+        std::fs::write(&path, format!("#!/bin/sh\n{}", script)).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    fn remove_stub(server: &Path) {
+        if let Some(dir) = server.parent() {
+            let _ = std::fs::remove_dir_all(dir);
+        }
+    }
+
+    // Answers every request until `quit`, waiting as many seconds as an
+    // option is named
+    const ECHO: &str = "echo ok
+while read command arg rest; do
+    case \"$command\" in
+        get_number_option) sleep \"$arg\"; echo \"ok $arg\" ;;
+        quit) exit 0 ;;
+        *) echo ok ;;
+    esac
+done
+";
+
+    #[test]
+    fn hanging_servers_time_out() {
+        let server = stub_server("hang", "echo ok\nread request\nexec sleep 60\n");
+        let pool = MeshPool::new(2)
+            .server(&server)
+            .timeout(Duration::from_millis(200))
+            .retries(1);
+        let results = pool.run(&[1, 2], |gmsh, _| gmsh.get_number_option("x"));
+        remove_stub(&server);
+        for result in results {
+            match result {
+                Err(GmshError::Timeout) => (),
+                _ => panic!(),
+            }
+        }
+    }
+
+    #[test]
+    fn exiting_servers_are_retried() {
+        // every start is counted, then the server quits without answering
+        let server = stub_server("exit", "echo start >> $DIR/starts\necho ok\nexit 0\n");
+        let pool = MeshPool::new(1).server(&server).retries(2);
+        let results = pool.run(&[1], |gmsh, _| gmsh.get_number_option("x"));
+        let starts = std::fs::read_to_string(server.with_file_name("starts")).unwrap();
+        remove_stub(&server);
+        match results[0] {
+            Err(GmshError::Disconnected) => (),
+            _ => panic!(),
+        }
+        assert_eq!(starts.lines().count(), 3);
+    }
+
+    #[test]
+    fn crashed_jobs_succeed_on_retry() {
+        // only the first server quits
+        let script = format!(
+            "if [ ! -e $DIR/crashed ]; then touch $DIR/crashed; echo ok; exit 0; fi\n{}",
+            ECHO
+        );
+        let server = stub_server("crash", &script);
+        let pool = MeshPool::new(1).server(&server).retries(1);
+        let results = pool.run(&[0.], |gmsh, &x| gmsh.get_number_option(&x.to_string()));
+        remove_stub(&server);
+        match results[0] {
+            Ok(x) => assert_eq!(x, 0.),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn results_keep_input_order() {
+        let server = stub_server("order", ECHO);
+        // later inputs finish first
+        let inputs = [0.4, 0.3, 0.2, 0.1, 0.];
+        let pool = MeshPool::new(inputs.len()).server(&server);
+        let results = pool.run(&inputs, |gmsh, &x| gmsh.get_number_option(&x.to_string()));
+        remove_stub(&server);
+        let results: Vec<f64> = results.into_iter().map(Result::unwrap).collect();
+        assert_eq!(results, inputs);
+    }
+}
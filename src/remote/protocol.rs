@@ -0,0 +1,260 @@
+// The line protocol between `RemoteGmsh` and the server.
+//
+// Every request and response is a single line of space-separated tokens.
+// Requests start with a command name, responses with `ok` followed by the
+// results, or `err` followed by the name of a `GmshError`. Strings are escaped
+// so they fit in one token, and lists are prefixed with their length.
+
+use crate::model::{element_type_from_raw, MeshElements, MeshNodes};
+use crate::remote::ExtractedMesh;
+use crate::{GmshError, GmshResult};
+use std::fmt::Display;
+use std::str::{FromStr, Split};
+
+// Make a string fit in one token
+fn escape(s: &str) -> String {
+    if s.is_empty() {
+        return "\\e".to_string();
+    }
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ' ' => escaped.push_str("\\s"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn unescape(token: &str) -> GmshResult<String> {
+    if token == "\\e" {
+        return Ok(String::new());
+    }
+    let mut s = String::with_capacity(token.len());
+    let mut chars = token.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            s.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('\\') => s.push('\\'),
+            Some('s') => s.push(' '),
+            Some('n') => s.push('\n'),
+            Some('r') => s.push('\r'),
+            _ => return Err(GmshError::CInterface),
+        }
+    }
+    Ok(s)
+}
+
+// A request or response being written
+pub(crate) struct Line(String);
+
+impl Line {
+    pub(crate) fn new(word: &str) -> Line {
+        Line(word.to_string())
+    }
+
+    pub(crate) fn ok() -> Line {
+        Line::new("ok")
+    }
+
+    pub(crate) fn err(e: &GmshError) -> Line {
+        Line::new("err").word(error_name(e))
+    }
+
+    fn word(mut self, word: &str) -> Line {
+        self.0.push(' ');
+        self.0.push_str(word);
+        self
+    }
+
+    pub(crate) fn num<T: Display>(self, value: T) -> Line {
+        self.word(&value.to_string())
+    }
+
+    pub(crate) fn str(self, value: &str) -> Line {
+        self.word(&escape(value))
+    }
+
+    pub(crate) fn list<T: Display>(self, values: &[T]) -> Line {
+        values
+            .iter()
+            .fold(self.num(values.len()), |line, v| line.num(v))
+    }
+
+    pub(crate) fn mesh(self, mesh: &ExtractedMesh) -> Line {
+        let nodes = &mesh.nodes;
+        let mut line = self
+            .list(&nodes.tags)
+            .list(&nodes.coords)
+            .list(&nodes.parametric_coords)
+            .num(mesh.elements.len());
+        for elements in &mesh.elements {
+            line = line
+                .num(elements.element_type.to_raw())
+                .list(&elements.tags)
+                .list(&elements.node_tags);
+        }
+        line
+    }
+
+    pub(crate) fn finish(self) -> String {
+        self.0
+    }
+}
+
+// A request or response being read
+pub(crate) struct Tokens<'a>(Split<'a, char>);
+
+impl<'a> Tokens<'a> {
+    pub(crate) fn new(line: &'a str) -> Tokens<'a> {
+        Tokens(line.trim_end().split(' '))
+    }
+
+    // Read a response, turning `err` responses into errors
+    pub(crate) fn response(line: &'a str) -> GmshResult<Tokens<'a>> {
+        let mut tokens = Tokens::new(line);
+        match tokens.0.next() {
+            Some("ok") => Ok(tokens),
+            Some("err") => Err(tokens
+                .0
+                .next()
+                .map_or(GmshError::UnknownError, error_from_name)),
+            _ => Err(GmshError::CInterface),
+        }
+    }
+
+    pub(crate) fn num<T: FromStr>(&mut self) -> GmshResult<T> {
+        self.0
+            .next()
+            .and_then(|token| token.parse().ok())
+            .ok_or(GmshError::CInterface)
+    }
+
+    pub(crate) fn str(&mut self) -> GmshResult<String> {
+        self.0
+            .next()
+            .ok_or(GmshError::CInterface)
+            .and_then(unescape)
+    }
+
+    pub(crate) fn list<T: FromStr>(&mut self) -> GmshResult<Vec<T>> {
+        let len: usize = self.num()?;
+        (0..len).map(|_| self.num()).collect()
+    }
+
+    pub(crate) fn mesh(&mut self) -> GmshResult<ExtractedMesh> {
+        let nodes = MeshNodes {
+            tags: self.list()?,
+            coords: self.list()?,
+            parametric_coords: self.list()?,
+        };
+        // counts come from the other process, so don't allocate by them
+        let num_types: usize = self.num()?;
+        let mut elements = Vec::new();
+        for _ in 0..num_types {
+            elements.push(MeshElements {
+                element_type: element_type_from_raw(self.num()?)?,
+                tags: self.list()?,
+                node_tags: self.list()?,
+            });
+        }
+        Ok(ExtractedMesh { nodes, elements })
+    }
+}
+
+fn error_name(e: &GmshError) -> &'static str {
+    match e {
+        GmshError::Initialization => "Initialization",
+        GmshError::Execution => "Execution",
+        GmshError::AlreadyInitialized => "AlreadyInitialized",
+        GmshError::Disconnected => "Disconnected",
+        GmshError::Timeout => "Timeout",
+        GmshError::CInterface => "CInterface",
+        GmshError::ModelMutation => "ModelMutation",
+        GmshError::ModelLookup => "ModelLookup",
+        GmshError::ModelBadInput => "ModelBadInput",
+        GmshError::ModelParallelMeshQuery => "ModelParallelMeshQuery",
+        GmshError::UnknownOption => "UnknownOption",
+        GmshError::UnknownError => "UnknownError",
+    }
+}
+
+fn error_from_name(name: &str) -> GmshError {
+    match name {
+        "Initialization" => GmshError::Initialization,
+        "Execution" => GmshError::Execution,
+        "AlreadyInitialized" => GmshError::AlreadyInitialized,
+        "Disconnected" => GmshError::Disconnected,
+        "Timeout" => GmshError::Timeout,
+        "CInterface" => GmshError::CInterface,
+        "ModelMutation" => GmshError::ModelMutation,
+        "ModelLookup" => GmshError::ModelLookup,
+        "ModelBadInput" => GmshError::ModelBadInput,
+        "ModelParallelMeshQuery" => GmshError::ModelParallelMeshQuery,
+        "UnknownOption" => GmshError::UnknownOption,
+        _ => GmshError::UnknownError,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ElementType;
+
+    #[test]
+    fn strings_round_trip() -> GmshResult<()> {
+        let names = ["", "box", "two words", "back\\slash", "multi\nline"];
+        let line = names
+            .iter()
+            .fold(Line::ok(), |line, n| line.str(n))
+            .finish();
+        let mut tokens = Tokens::response(&line)?;
+        for name in names.iter() {
+            assert_eq!(&tokens.str()?, name);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn meshes_round_trip() -> GmshResult<()> {
+        let mesh = ExtractedMesh {
+            nodes: MeshNodes {
+                tags: vec![1, 2, 3],
+                coords: vec![0., 0., 0., 1., 0., 0., 0.1, 1. / 3., 1e-12],
+                parametric_coords: vec![],
+            },
+            elements: vec![MeshElements {
+                element_type: ElementType::Triangle3,
+                tags: vec![7],
+                node_tags: vec![1, 2, 3],
+            }],
+        };
+        let line = Line::ok().mesh(&mesh).finish();
+        assert_eq!(Tokens::response(&line)?.mesh()?, mesh);
+        Ok(())
+    }
+
+    #[test]
+    fn huge_counts_fail_cleanly() {
+        let line = format!("ok 0 0 0 {}", usize::MAX);
+        match Tokens::response(&line).and_then(|mut tokens| tokens.mesh()) {
+            Err(GmshError::CInterface) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn errors_round_trip() {
+        let line = Line::err(&GmshError::ModelLookup).finish();
+        match Tokens::response(&line) {
+            Err(GmshError::ModelLookup) => (),
+            _ => panic!(),
+        }
+    }
+}
@@ -0,0 +1,222 @@
+//! The server side of [`RemoteGmsh`](super::RemoteGmsh).
+//!
+//! The `rgmsh-server` binary runs [`serve`] on its standard input and output.
+
+use super::protocol::{Line, Tokens};
+use super::ExtractedMesh;
+use crate::io::FileFormat;
+use crate::model::{CurveTag, GeoModel, OccModel, PointTag, WireTag};
+use crate::{Gmsh, GmshError, GmshResult};
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+
+enum ServerModel<'gmsh> {
+    Geo(GeoModel<'gmsh>),
+    Occ(OccModel<'gmsh>),
+}
+
+// Run an expression on either kind of model
+macro_rules! on_model {
+    ($model: expr, $m: ident => $body: expr) => {
+        match $model {
+            ServerModel::Geo($m) => $body,
+            ServerModel::Occ($m) => $body,
+        }
+    };
+}
+
+/// Initialize Gmsh and answer requests until the input ends or a `quit`
+/// request arrives.
+pub fn serve<R: BufRead, W: Write>(input: R, mut output: W) -> GmshResult<()> {
+    let gmsh = Gmsh::initialize();
+    // tell the client whether Gmsh started
+    let hello = match &gmsh {
+        Ok(_) => Line::ok(),
+        Err(e) => Line::err(e),
+    };
+    respond(&mut output, hello)?;
+    let gmsh = gmsh?;
+
+    let mut models = HashMap::new();
+    for line in input.lines() {
+        let line = line.map_err(|_| GmshError::Disconnected)?;
+        let mut tokens = Tokens::new(&line);
+        // a garbled request gets an error, it doesn't end the server
+        let response = match tokens.str() {
+            Ok(command) if command == "quit" => break,
+            Ok(command) => match answer(&gmsh, &mut models, &command, &mut tokens) {
+                Ok(response) => response,
+                Err(e) => Line::err(&e),
+            },
+            Err(e) => Line::err(&e),
+        };
+        respond(&mut output, response)?;
+    }
+    Ok(())
+}
+
+fn respond<W: Write>(output: &mut W, line: Line) -> GmshResult<()> {
+    writeln!(output, "{}", line.finish())
+        .and_then(|_| output.flush())
+        .map_err(|_| GmshError::Disconnected)
+}
+
+fn answer<'gmsh>(
+    gmsh: &'gmsh Gmsh,
+    models: &mut HashMap<String, ServerModel<'gmsh>>,
+    command: &str,
+    tokens: &mut Tokens,
+) -> GmshResult<Line> {
+    match command {
+        "new_model" => {
+            let kernel = tokens.str()?;
+            let name = tokens.str()?;
+            let model = match kernel.as_str() {
+                "geo" => ServerModel::Geo(gmsh.create_native_model(name.as_str())?),
+                "occ" => ServerModel::Occ(gmsh.create_occ_model(name.as_str())?),
+                _ => return Err(GmshError::ModelBadInput),
+            };
+            models.insert(name, model);
+            Ok(Line::ok())
+        }
+        "clear" => {
+            models.clear();
+            gmsh.clear_shared()?;
+            Ok(Line::ok())
+        }
+        "open" => {
+            let path = tokens.str()?;
+            // CAD files belong to the OpenCASCADE kernel, everything else to the built-in one
+            let occ = matches!(
+                FileFormat::from_path(&path),
+                Some(FileFormat::Step) | Some(FileFormat::Iges) | Some(FileFormat::Brep)
+            );
+            let opened = gmsh.open(&path)?;
+            let mut response = Line::ok().num(opened.len());
+            for model in opened {
+                let name = model.name;
+                let model = if occ {
+                    response = response.str(&name).str("occ");
+                    ServerModel::Occ(gmsh.occ_model(name.as_str())?)
+                } else {
+                    response = response.str(&name).str("geo");
+                    ServerModel::Geo(gmsh.native_model(name.as_str())?)
+                };
+                models.insert(name, model);
+            }
+            Ok(response)
+        }
+        "remove_model" => {
+            let model = models
+                .remove(&tokens.str()?)
+                .ok_or(GmshError::ModelLookup)?;
+            on_model!(model, m => m.remove())?;
+            Ok(Line::ok())
+        }
+        "set_number_option" => {
+            let name = tokens.str()?;
+            gmsh.set_number_option(&name, tokens.num()?)?;
+            Ok(Line::ok())
+        }
+        "set_string_option" => {
+            let name = tokens.str()?;
+            gmsh.set_string_option(&name, &tokens.str()?)?;
+            Ok(Line::ok())
+        }
+        "get_number_option" => Ok(Line::ok().num(gmsh.get_number_option(&tokens.str()?)?)),
+        _ => {
+            let model = models
+                .get_mut(&tokens.str()?)
+                .ok_or(GmshError::ModelLookup)?;
+            answer_model(model, command, tokens)
+        }
+    }
+}
+
+fn answer_model(model: &mut ServerModel, command: &str, tokens: &mut Tokens) -> GmshResult<Line> {
+    match command {
+        "add_point" => {
+            let (x, y, z) = (tokens.num()?, tokens.num()?, tokens.num()?);
+            let tag = on_model!(model, m => m.add_point(x, y, z))?;
+            Ok(Line::ok().num(tag.0))
+        }
+        "add_point_with_lc" => {
+            let (x, y, z) = (tokens.num()?, tokens.num()?, tokens.num()?);
+            let lc = tokens.num()?;
+            let tag = on_model!(model, m => m.add_point_with_lc(x, y, z, lc))?;
+            Ok(Line::ok().num(tag.0))
+        }
+        "add_line" => {
+            let (p1, p2) = (PointTag(tokens.num()?), PointTag(tokens.num()?));
+            let tag = on_model!(model, m => m.add_line(p1, p2))?;
+            Ok(Line::ok().num(tag.0))
+        }
+        "add_curve_loop" => {
+            let curves: Vec<_> = tokens.list()?.into_iter().map(CurveTag).collect();
+            let tag = on_model!(model, m => m.add_curve_loop(&curves))?;
+            Ok(Line::ok().num(tag.0))
+        }
+        "add_plane_surface" => {
+            let boundary = WireTag(tokens.num()?);
+            let holes: Vec<_> = tokens.list()?.into_iter().map(WireTag).collect();
+            let tag = on_model!(model, m => m.add_plane_surface_with_holes(boundary, &holes))?;
+            Ok(Line::ok().num(tag.0))
+        }
+        "add_box" => {
+            let start = (tokens.num()?, tokens.num()?, tokens.num()?);
+            let extents = (tokens.num()?, tokens.num()?, tokens.num()?);
+            match model {
+                ServerModel::Occ(m) => Ok(Line::ok().num(m.add_box(start, extents)?.0)),
+                ServerModel::Geo(_) => Err(GmshError::ModelBadInput),
+            }
+        }
+        "add_sphere" => {
+            let centroid = (tokens.num()?, tokens.num()?, tokens.num()?);
+            let radius = tokens.num()?;
+            match model {
+                ServerModel::Occ(m) => Ok(Line::ok().num(m.add_sphere(centroid, radius)?.0)),
+                ServerModel::Geo(_) => Err(GmshError::ModelBadInput),
+            }
+        }
+        "import_shapes" => {
+            let path = tokens.str()?;
+            match model {
+                ServerModel::Occ(m) => {
+                    let dim_tags: Vec<i32> = m
+                        .import_shapes(path)?
+                        .into_iter()
+                        .flat_map(|shape| {
+                            let (dim, tag) = shape.dim_tag();
+                            vec![dim, tag]
+                        })
+                        .collect();
+                    Ok(Line::ok().list(&dim_tags))
+                }
+                ServerModel::Geo(_) => Err(GmshError::ModelBadInput),
+            }
+        }
+        "synchronize" => {
+            on_model!(model, m => m.synchronize())?;
+            Ok(Line::ok())
+        }
+        "generate_mesh" => {
+            let dim = tokens.num()?;
+            on_model!(model, m => m.generate_mesh(dim))?;
+            Ok(Line::ok())
+        }
+        "get_mesh" => {
+            let dim = tokens.num()?;
+            let mesh = on_model!(model, m => {
+                let nodes = m.get_nodes_gen(-1, -1, false)?;
+                let elements = m
+                    .get_element_types_gen(dim, -1)?
+                    .into_iter()
+                    .map(|element_type| m.get_elements_by_type_gen(element_type, -1))
+                    .collect::<GmshResult<Vec<_>>>()?;
+                ExtractedMesh { nodes, elements }
+            });
+            Ok(Line::ok().mesh(&mesh))
+        }
+        _ => Err(GmshError::Execution),
+    }
+}
@@ -0,0 +1,326 @@
+//! Run Gmsh on a dedicated worker thread.
+//!
+//! [`Gmsh`] must stay on the thread that initialized it. A [`GmshService`]
+//! owns the context on its own thread and hands out [`GmshHandle`]s, which can
+//! be cloned and shared between threads. Work is sent to the worker as
+//! closures, run one at a time, and the results are sent back.
+//! ```no_run
+//! # use rgmsh::GmshResult;
+//! use rgmsh::service::GmshService;
+//!
+//! # fn main() -> GmshResult<()> {
+//! let service = GmshService::start()?;
+//! let handle = service.handle();
+//!
+//! let worker = std::thread::spawn(move || {
+//!     handle.run(|gmsh| -> GmshResult<usize> {
+//!         let mut geom = gmsh.create_occ_model("box")?;
+//!         let b = geom.add_box((0., 0., 0.), (1., 1., 1.))?;
+//!         geom.generate_mesh(3)?;
+//!         Ok(geom.get_nodes(b, true)?.tags.len())
+//!     })
+//! });
+//! let num_nodes = worker.join().unwrap()??;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ## Async
+//! Handles also queue work without waiting for it, returning a [`GmshFuture`]
+//! that works with any executor. Long meshing jobs don't block executor threads.
+//! ```no_run
+//! # use rgmsh::GmshResult;
+//! # use rgmsh::service::GmshHandle;
+//! async fn mesh(handle: GmshHandle) -> GmshResult<()> {
+//!     handle
+//!         .run_async(|gmsh| -> GmshResult<()> {
+//!             let mut geom = gmsh.create_occ_model("sphere")?;
+//!             geom.add_sphere((0., 0., 0.), 1.)?;
+//!             Ok(())
+//!         })
+//!         .await??;
+//!     handle.generate_mesh("sphere", 3).await
+//! }
+//! ```
+//! Dropping a future cancels its job if the job hasn't started yet. The Gmsh
+//! 4.4.1 API has no call to abort meshing or any other running operation, so a
+//! running job always finishes and its result is thrown away.
+
+use crate::model::{BasicShape, BooleanOp};
+use crate::{Gmsh, GmshError, GmshResult};
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread::{self, JoinHandle};
+
+// Work for the Gmsh thread
+type Job = Box<dyn FnOnce(&mut Gmsh) + Send>;
+
+enum Message {
+    Run(Job),
+    Shutdown,
+}
+
+/// Owns a Gmsh context running on a dedicated thread.
+///
+/// Dropping the service finishes any queued work, then finalizes Gmsh. Handles
+/// that outlive the service get `Disconnected` errors.
+pub struct GmshService {
+    handle: GmshHandle,
+    thread: Option<JoinHandle<()>>,
+}
+
+/// A `Send + Sync` handle to a [`GmshService`].
+#[derive(Clone)]
+pub struct GmshHandle {
+    // std senders aren't Sync on older compilers
+    sender: Arc<Mutex<Sender<Message>>>,
+}
+
+impl GmshService {
+    /// Start the worker thread and initialize Gmsh on it.
+    pub fn start() -> GmshResult<GmshService> {
+        let (sender, receiver) = mpsc::channel();
+        let (init_sender, init_receiver) = mpsc::channel();
+        let thread = thread::Builder::new()
+            .name("gmsh".to_string())
+            .spawn(move || match Gmsh::initialize() {
+                Ok(mut gmsh) => {
+                    let _ = init_sender.send(Ok(()));
+                    serve(&mut gmsh, receiver);
+                }
+                Err(e) => {
+                    let _ = init_sender.send(Err(e));
+                }
+            })
+            .map_err(|_| GmshError::Initialization)?;
+
+        init_receiver
+            .recv()
+            .map_err(|_| GmshError::Disconnected)??;
+        Ok(GmshService {
+            handle: GmshHandle {
+                sender: Arc::new(Mutex::new(sender)),
+            },
+            thread: Some(thread),
+        })
+    }
+
+    /// Get a handle to send work to the Gmsh thread.
+    pub fn handle(&self) -> GmshHandle {
+        self.handle.clone()
+    }
+}
+
+impl Drop for GmshService {
+    fn drop(&mut self) {
+        let _ = self.handle.send(Message::Shutdown);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+// Run jobs until the service shuts down
+fn serve(gmsh: &mut Gmsh, receiver: Receiver<Message>) {
+    for message in receiver {
+        match message {
+            Message::Run(job) => job(gmsh),
+            Message::Shutdown => break,
+        }
+    }
+}
+
+impl GmshHandle {
+    fn send(&self, message: Message) -> GmshResult<()> {
+        let sender = self.sender.lock().map_err(|_| GmshError::Disconnected)?;
+        sender.send(message).map_err(|_| GmshError::Disconnected)
+    }
+
+    /// Run a function on the Gmsh thread and wait for its result.
+    ///
+    /// A panic in `f` is passed on to the caller, and the worker keeps running.
+    pub fn run<F, R>(&self, f: F) -> GmshResult<R>
+    where
+        F: FnOnce(&mut Gmsh) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let receiver = self.submit(f)?;
+        match receiver.recv() {
+            Ok(Ok(result)) => Ok(result),
+            Ok(Err(panic)) => panic::resume_unwind(panic),
+            Err(_) => Err(GmshError::Disconnected),
+        }
+    }
+
+    // Queue a function, returning where its result will arrive
+    pub(crate) fn submit<F, R>(&self, f: F) -> GmshResult<Receiver<thread::Result<R>>>
+    where
+        F: FnOnce(&mut Gmsh) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let (result_sender, result_receiver) = mpsc::channel();
+        let job: Job = Box::new(move |gmsh: &mut Gmsh| {
+            let result = panic::catch_unwind(AssertUnwindSafe(|| f(gmsh)));
+            // the caller may have stopped waiting
+            let _ = result_sender.send(result);
+        });
+        self.send(Message::Run(job))?;
+        Ok(result_receiver)
+    }
+}
+
+// State shared by a future and its job
+struct Shared<R> {
+    result: Option<thread::Result<R>>,
+    waker: Option<Waker>,
+    // the job finished or was dropped by the worker
+    done: bool,
+    // the future was dropped
+    cancelled: bool,
+}
+
+/// The result of a job queued with [`GmshHandle::run_async`].
+///
+/// Resolves to `Disconnected` if the worker stops before running the job.
+#[must_use = "futures do nothing unless awaited, and dropping one cancels its job"]
+pub struct GmshFuture<R> {
+    shared: Arc<Mutex<Shared<R>>>,
+}
+
+// Owned by the job, reports its result to the future
+struct Completer<R> {
+    shared: Arc<Mutex<Shared<R>>>,
+}
+
+impl<R> Completer<R> {
+    fn is_cancelled(&self) -> bool {
+        self.shared.lock().map(|s| s.cancelled).unwrap_or(true)
+    }
+
+    fn complete(self, result: thread::Result<R>) {
+        if let Ok(mut shared) = self.shared.lock() {
+            shared.result = Some(result);
+        }
+        // dropping wakes the future
+    }
+}
+
+impl<R> Drop for Completer<R> {
+    fn drop(&mut self) {
+        if let Ok(mut shared) = self.shared.lock() {
+            shared.done = true;
+            if let Some(waker) = shared.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+impl<R> Future for GmshFuture<R> {
+    type Output = GmshResult<R>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<GmshResult<R>> {
+        let mut shared = match self.shared.lock() {
+            Ok(shared) => shared,
+            Err(_) => return Poll::Ready(Err(GmshError::Disconnected)),
+        };
+        match shared.result.take() {
+            Some(Ok(result)) => Poll::Ready(Ok(result)),
+            Some(Err(panic)) => {
+                drop(shared);
+                panic::resume_unwind(panic)
+            }
+            None if shared.done => Poll::Ready(Err(GmshError::Disconnected)),
+            None => {
+                shared.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<R> Drop for GmshFuture<R> {
+    fn drop(&mut self) {
+        if let Ok(mut shared) = self.shared.lock() {
+            shared.cancelled = true;
+        }
+    }
+}
+
+impl GmshHandle {
+    /// Queue a function to run on the Gmsh thread without waiting for it.
+    ///
+    /// A panic in `f` is passed on to whoever polls the future.
+    pub fn run_async<F, R>(&self, f: F) -> GmshFuture<R>
+    where
+        F: FnOnce(&mut Gmsh) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let shared = Arc::new(Mutex::new(Shared {
+            result: None,
+            waker: None,
+            done: false,
+            cancelled: false,
+        }));
+        let completer = Completer {
+            shared: shared.clone(),
+        };
+        let job: Job = Box::new(move |gmsh: &mut Gmsh| {
+            if completer.is_cancelled() {
+                return;
+            }
+            completer.complete(panic::catch_unwind(AssertUnwindSafe(|| f(gmsh))));
+        });
+        // if the worker is gone the job is dropped here, resolving the future
+        let _ = self.send(Message::Run(job));
+        GmshFuture { shared }
+    }
+
+    /// Import the shapes of a CAD file into an `OpenCASCADE` model, by name,
+    /// without blocking.
+    pub fn import_shapes<P: AsRef<Path>>(
+        &self,
+        model: &str,
+        path: P,
+    ) -> impl Future<Output = GmshResult<Vec<BasicShape>>> {
+        let model = model.to_string();
+        let path = path.as_ref().to_path_buf();
+        let job =
+            self.run_async(move |gmsh: &mut Gmsh| gmsh.occ_model(model)?.import_shapes(&path));
+        async move { job.await? }
+    }
+
+    /// Apply a boolean operation in an `OpenCASCADE` model, by name, without blocking.
+    pub fn boolean(
+        &self,
+        model: &str,
+        op: BooleanOp,
+        objects: Vec<BasicShape>,
+        tools: Vec<BasicShape>,
+    ) -> impl Future<Output = GmshResult<Vec<BasicShape>>> {
+        let model = model.to_string();
+        let job = self
+            .run_async(move |gmsh: &mut Gmsh| gmsh.occ_model(model)?.boolean(op, &objects, &tools));
+        async move { job.await? }
+    }
+
+    /// Mesh a model, by name, without blocking. Pending geometry changes are
+    /// synchronized first.
+    pub fn generate_mesh(&self, model: &str, dim: i32) -> impl Future<Output = GmshResult<()>> {
+        let model = model.to_string();
+        let job = self.run_async(move |gmsh: &mut Gmsh| gmsh.model(model)?.generate_mesh(dim));
+        async move { job.await? }
+    }
+
+    /// Write the current model to a file without blocking.
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> impl Future<Output = GmshResult<()>> {
+        let path = path.as_ref().to_path_buf();
+        let job = self.run_async(move |gmsh: &mut Gmsh| gmsh.write(&path));
+        async move { job.await? }
+    }
+}
@@ -0,0 +1,62 @@
+//! Helpers for testing code that uses Gmsh.
+//!
+//! Only one Gmsh context can be alive at a time, but `cargo test` runs tests
+//! in parallel. Tests that get their context from [`serial_gmsh`] take turns
+//! instead of failing with `AlreadyInitialized`.
+//! ```no_run
+//! # use rgmsh::GmshResult;
+//! use rgmsh::testing::serial_gmsh;
+//!
+//! #[test]
+//! fn makes_a_box() -> GmshResult<()> {
+//!     let gmsh = serial_gmsh()?;
+//!     let mut geom = gmsh.create_occ_model("box")?;
+//!     geom.add_box((0., 0., 0.), (1., 1., 1.))?;
+//!     Ok(())
+//! }
+//! ```
+
+use crate::{Gmsh, GmshResult};
+use std::ops::{Deref, DerefMut};
+use std::sync::{Mutex, MutexGuard};
+
+// Held by whoever is using Gmsh through `serial_gmsh`
+static GMSH_LOCK: Mutex<()> = Mutex::new(());
+
+/// A Gmsh context that other `serial_gmsh` users wait for.
+pub struct SerialGmsh {
+    // finalize Gmsh before letting the next user in
+    gmsh: Gmsh,
+    _lock: MutexGuard<'static, ()>,
+}
+
+/// Wait for any other `serial_gmsh` user to finish, then initialize Gmsh.
+pub fn serial_gmsh() -> GmshResult<SerialGmsh> {
+    let lock = lock();
+    Ok(SerialGmsh {
+        gmsh: Gmsh::initialize()?,
+        _lock: lock,
+    })
+}
+
+// Take a turn without initializing Gmsh, e.g. to start it on another thread
+pub(crate) fn lock() -> MutexGuard<'static, ()> {
+    // a panicking test poisons the lock, but its context was still finalized
+    GMSH_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+impl Deref for SerialGmsh {
+    type Target = Gmsh;
+
+    fn deref(&self) -> &Gmsh {
+        &self.gmsh
+    }
+}
+
+impl DerefMut for SerialGmsh {
+    fn deref_mut(&mut self) -> &mut Gmsh {
+        &mut self.gmsh
+    }
+}
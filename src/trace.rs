@@ -0,0 +1,89 @@
+//! Opt-in trace of API calls.
+//!
+//! When tracing is on, geometry and meshing calls are written to a replay log,
+//! one call per line with the model name, the arguments and the result:
+//! ```text
+//! [box] add_point(0.0, 0.0, 0.0) -> Ok(PointTag(1))
+//! [box] add_point_with_lc(1.0, 0.0, 0.0, 0.1) -> Ok(PointTag(2))
+//! [box] add_line(PointTag(1), PointTag(2)) -> Ok(CurveTag(1))
+//! ```
+//! Tracing is off by default and costs a single atomic load per call when off.
+//! At [`Verbosity::Debug`] traced calls are also logged with the `"rgmsh"` target.
+//! ```no_run
+//! # use rgmsh::{Gmsh, GmshResult};
+//! # fn main() -> GmshResult<()> {
+//! let mut gmsh = Gmsh::initialize()?;
+//! let log = std::fs::File::create("calls.log").unwrap();
+//! gmsh.start_trace(log);
+//!
+//! let mut geom = gmsh.create_occ_model("box")?;
+//! let p1 = geom.add_point(0., 0., 0.)?;
+//!
+//! gmsh.stop_trace();
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::logger::Verbosity;
+use crate::Gmsh;
+use std::fmt::Debug;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+static TRACING: AtomicBool = AtomicBool::new(false);
+
+// where traced calls are written
+static SINK: Mutex<Option<Box<dyn Write + Send>>> = Mutex::new(None);
+
+#[doc(hidden)]
+// check whether calls are traced before formatting them
+pub fn is_enabled() -> bool {
+    TRACING.load(Ordering::Relaxed)
+}
+
+#[doc(hidden)]
+// write one call to the trace
+pub fn record(model: &str, call: &str, result: &dyn Debug) {
+    let line = format!("[{}] {} -> {:?}", model, call, result);
+    if crate::logger::logs(Verbosity::Debug) {
+        log::debug!(target: "rgmsh", "{}", line);
+    }
+    if let Ok(mut sink) = SINK.lock() {
+        if let Some(writer) = sink.as_mut() {
+            // a broken trace shouldn't break the traced program
+            let _ = writeln!(writer, "{}", line);
+        }
+    }
+}
+
+/// Trace a call and its result if tracing is on.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! trace_call {
+    ($model: expr, $fmt: literal $(, $arg: expr)* => $result: expr) => {
+        if $crate::trace::is_enabled() {
+            $crate::trace::record(&$model, &format!($fmt $(, $arg)*), &$result);
+        }
+    };
+}
+
+impl Gmsh {
+    /// Start writing every traced API call to `writer`.
+    pub fn start_trace<W: Write + Send + 'static>(&mut self, writer: W) {
+        if let Ok(mut sink) = SINK.lock() {
+            *sink = Some(Box::new(writer));
+        }
+        TRACING.store(true, Ordering::Relaxed);
+    }
+
+    /// Stop tracing API calls and flush the trace.
+    pub fn stop_trace(&mut self) {
+        TRACING.store(false, Ordering::Relaxed);
+        if let Ok(mut sink) = SINK.lock() {
+            if let Some(mut writer) = sink.take() {
+                let _ = writer.flush();
+            }
+        }
+    }
+}
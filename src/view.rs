@@ -0,0 +1,325 @@
+//! Post-processing views.
+//!
+//! Views hold data to display on top of a model, like solver results. Data can
+//! be attached to the nodes or elements of a model mesh, or given as a list of
+//! standalone elements with their coordinates.
+//! ```
+//! # use rgmsh::{Gmsh, GmshResult};
+//! # use rgmsh::model::ElementType;
+//! # use rgmsh::view::ViewDataType;
+//! # fn main() -> GmshResult<()> {
+//! let gmsh = Gmsh::initialize()?;
+//! let mut geom = gmsh.create_occ_model("model")?;
+//! let b = geom.add_box((0., 0., 0.), (1., 1., 1.))?;
+//! geom.generate_mesh(3)?;
+//!
+//! // a scalar temperature at each node
+//! let nodes = geom.get_nodes(b, true)?;
+//! let temperature: Vec<f64> = nodes.coords.chunks(3).map(|p| 20. + p[2]).collect();
+//!
+//! let view = gmsh.add_view("temperature")?;
+//! gmsh.add_model_data(view, 0, &geom.name, ViewDataType::NodeData, &nodes.tags, &temperature, 0., 1)?;
+//!
+//! let at_top = gmsh.probe_view(view, (0.5, 0.5, 1.))?;
+//! gmsh.write_view(view, "temperature.pos")?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::interface::{string_from_raw, vec_from_raw};
+use crate::io::path_str;
+use crate::{check_model_error, get_cstring, Gmsh, GmshError, GmshResult};
+use std::os::raw::{c_char, c_int};
+use std::path::Path;
+
+/// A post-processing view tag.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ViewTag(pub(crate) i32);
+
+/// Where model data values are attached in the mesh.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ViewDataType {
+    /// One set of values per node.
+    NodeData,
+    /// One set of values per element.
+    ElementData,
+    /// One set of values per node of each element.
+    ElementNodeData,
+}
+
+impl ViewDataType {
+    /// The data type name Gmsh uses.
+    fn as_str(self) -> &'static str {
+        match self {
+            ViewDataType::NodeData => "NodeData",
+            ViewDataType::ElementData => "ElementData",
+            ViewDataType::ElementNodeData => "ElementNodeData",
+        }
+    }
+
+    /// Parse a data type name from Gmsh.
+    fn from_name(name: &str) -> GmshResult<ViewDataType> {
+        match name {
+            "NodeData" => Ok(ViewDataType::NodeData),
+            "ElementData" => Ok(ViewDataType::ElementData),
+            "ElementNodeData" => Ok(ViewDataType::ElementNodeData),
+            _ => Err(GmshError::CInterface),
+        }
+    }
+}
+
+/// Model data stored in a view for one time step.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViewModelData {
+    /// Where the values are attached.
+    pub data_type: ViewDataType,
+    /// The node or element tags.
+    pub tags: Vec<usize>,
+    /// The values for each tag.
+    pub data: Vec<Vec<f64>>,
+    /// The time value of the step.
+    pub time: f64,
+    /// The number of components of each value, e.g. 1 for scalars.
+    pub num_components: i32,
+}
+
+/// What to combine in `combine_views`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CombineWhat {
+    /// Combine views into time steps of a single view.
+    Steps,
+    /// Combine the elements of views into a single view.
+    Elements,
+}
+
+/// Which views to combine in `combine_views`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CombineHow {
+    /// All views.
+    All,
+    /// Visible views only.
+    Visible,
+    /// Views with the same name.
+    ByName,
+}
+
+impl Gmsh {
+    /// Add a new, empty post-processing view.
+    pub fn add_view(&self, name: &str) -> GmshResult<ViewTag> {
+        let c_name = get_cstring(name)?;
+        unsafe {
+            let mut ierr: c_int = 0;
+            let auto_number = -1;
+            let out_tag = gmsh_sys::gmshViewAdd(c_name.as_ptr(), auto_number, &mut ierr);
+            check_model_error!(ierr, ViewTag(out_tag))
+        }
+    }
+
+    /// Remove a view.
+    pub fn remove_view(&self, view: ViewTag) -> GmshResult<()> {
+        unsafe {
+            let mut ierr: c_int = 0;
+            gmsh_sys::gmshViewRemove(view.0, &mut ierr);
+            check_model_error!(ierr, ())
+        }
+    }
+
+    /// Add model data to a view for time step `step`.
+    ///
+    /// `data` holds the same number of values for each tag, e.g.
+    /// `num_components` values per node for `NodeData`. Fails with
+    /// `ModelBadInput` for empty or uneven data.
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_model_data(
+        &self,
+        view: ViewTag,
+        step: i32,
+        model_name: &str,
+        data_type: ViewDataType,
+        tags: &[usize],
+        data: &[f64],
+        time: f64,
+        num_components: i32,
+    ) -> GmshResult<()> {
+        if tags.is_empty() || data.is_empty() || data.len() % tags.len() != 0 {
+            return Err(GmshError::ModelBadInput);
+        }
+        // whole values for each tag, e.g. for every node of an element
+        let values_per_tag = data.len() / tags.len();
+        if num_components < 1 || values_per_tag % num_components as usize != 0 {
+            return Err(GmshError::ModelBadInput);
+        }
+        let c_model_name = get_cstring(model_name)?;
+        let c_data_type = get_cstring(data_type.as_str())?;
+
+        // split the values into one array per tag
+        let mut data_ptrs: Vec<*const f64> = data
+            .chunks(values_per_tag)
+            .map(|values| values.as_ptr())
+            .collect();
+        let data_lens = vec![values_per_tag; tags.len()];
+        unsafe {
+            let mut ierr: c_int = 0;
+            let partition = 0;
+            // Gmsh only reads the input arrays
+            gmsh_sys::gmshViewAddModelData(
+                view.0,
+                step,
+                c_model_name.as_ptr(),
+                c_data_type.as_ptr(),
+                tags.as_ptr() as *mut usize,
+                tags.len(),
+                data_ptrs.as_mut_ptr() as _,
+                data_lens.as_ptr() as _,
+                data_lens.len(),
+                time,
+                num_components,
+                partition,
+                &mut ierr,
+            );
+            check_model_error!(ierr, ())
+        }
+    }
+
+    /// Get the model data of a view for time step `step`.
+    pub fn get_model_data(&self, view: ViewTag, step: i32) -> GmshResult<ViewModelData> {
+        unsafe {
+            let mut ierr: c_int = 0;
+            let mut data_type: *mut c_char = std::ptr::null_mut();
+            let mut tags: *mut usize = std::ptr::null_mut();
+            let mut tags_n: usize = 0;
+            let mut data: *mut *mut f64 = std::ptr::null_mut();
+            let mut data_n: *mut usize = std::ptr::null_mut();
+            let mut data_nn: usize = 0;
+            let mut time: f64 = 0.;
+            let mut num_components: c_int = 0;
+            gmsh_sys::gmshViewGetModelData(
+                view.0,
+                step,
+                &mut data_type,
+                &mut tags,
+                &mut tags_n,
+                &mut data,
+                &mut data_n,
+                &mut data_nn,
+                &mut time,
+                &mut num_components,
+                &mut ierr,
+            );
+            let data_type = string_from_raw(data_type)?;
+            let tags = vec_from_raw(tags, tags_n);
+            // copy and free each inner array, then the outer arrays
+            let lens = vec_from_raw(data_n, data_nn);
+            let ptrs = vec_from_raw(data, data_nn);
+            let data = ptrs
+                .into_iter()
+                .zip(lens)
+                .map(|(ptr, len)| vec_from_raw(ptr, len))
+                .collect();
+            check_model_error!(ierr, ())?;
+            Ok(ViewModelData {
+                data_type: ViewDataType::from_name(&data_type)?,
+                tags,
+                data,
+                time,
+                num_components,
+            })
+        }
+    }
+
+    /// Add list data to a view: `num_elements` elements with their coordinates
+    /// and values. `data_type` is a Gmsh list type, e.g. `"ST"` for scalar
+    /// triangles or `"VP"` for vector points.
+    pub fn add_list_data(
+        &self,
+        view: ViewTag,
+        data_type: &str,
+        num_elements: i32,
+        data: &[f64],
+    ) -> GmshResult<()> {
+        let c_data_type = get_cstring(data_type)?;
+        unsafe {
+            let mut ierr: c_int = 0;
+            gmsh_sys::gmshViewAddListData(
+                view.0,
+                c_data_type.as_ptr(),
+                num_elements,
+                data.as_ptr() as *mut f64,
+                data.len(),
+                &mut ierr,
+            );
+            check_model_error!(ierr, ())
+        }
+    }
+
+    /// Interpolate the values of a view at a point, for all time steps and components.
+    pub fn probe_view(&self, view: ViewTag, point: (f64, f64, f64)) -> GmshResult<Vec<f64>> {
+        unsafe {
+            let mut ierr: c_int = 0;
+            let mut values: *mut f64 = std::ptr::null_mut();
+            let mut values_n: usize = 0;
+            let all_steps = -1;
+            let all_components = -1;
+            let gradient = 0;
+            let tolerance = 0.;
+            gmsh_sys::gmshViewProbe(
+                view.0,
+                point.0,
+                point.1,
+                point.2,
+                &mut values,
+                &mut values_n,
+                all_steps,
+                all_components,
+                gradient,
+                tolerance,
+                std::ptr::null_mut(),
+                0,
+                std::ptr::null_mut(),
+                0,
+                std::ptr::null_mut(),
+                0,
+                &mut ierr,
+            );
+            let values = vec_from_raw(values, values_n);
+            check_model_error!(ierr, values)
+        }
+    }
+
+    /// Combine views, optionally removing the originals.
+    pub fn combine_views(
+        &self,
+        what: CombineWhat,
+        how: CombineHow,
+        remove: bool,
+    ) -> GmshResult<()> {
+        let what = match what {
+            CombineWhat::Steps => "steps",
+            CombineWhat::Elements => "elements",
+        };
+        let how = match how {
+            CombineHow::All => "all",
+            CombineHow::Visible => "visible",
+            CombineHow::ByName => "by name",
+        };
+        let c_what = get_cstring(what)?;
+        let c_how = get_cstring(how)?;
+        unsafe {
+            let mut ierr: c_int = 0;
+            gmsh_sys::gmshViewCombine(c_what.as_ptr(), c_how.as_ptr(), remove as c_int, &mut ierr);
+            check_model_error!(ierr, ())
+        }
+    }
+
+    /// Write a view to a file. The format is deduced from the file extension,
+    /// e.g. `.pos` or `.msh`.
+    pub fn write_view<P: AsRef<Path>>(&self, view: ViewTag, path: P) -> GmshResult<()> {
+        let c_path = get_cstring(path_str(path.as_ref())?)?;
+        unsafe {
+            let mut ierr: c_int = 0;
+            let append = 0;
+            gmsh_sys::gmshViewWrite(view.0, c_path.as_ptr(), append, &mut ierr);
+            check_model_error!(ierr, ())
+        }
+    }
+}